@@ -0,0 +1,597 @@
+use std::collections::HashMap;
+
+use crate::{Number, Vector3, Vector4, Quaternion, SQuaternion, coordiante_system::CoordinateSytem3, vector::TToolRotation, transform::Transform3, curve::FrameCurve};
+
+/// 动画目标属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EAnimationProperty {
+    Translation,
+    Rotation,
+    Scaling,
+    /// 任意浮点属性
+    Float,
+}
+
+/// 通道曲线 - 旋转曲线值为未归一化四元数
+#[derive(Debug, Clone)]
+pub enum EAnimationCurve {
+    Translation(FrameCurve<Vector3>),
+    Rotation(FrameCurve<SQuaternion<Number>>),
+    Scaling(FrameCurve<Vector3>),
+    Float(FrameCurve<Number>),
+}
+
+impl EAnimationCurve {
+    pub fn property(&self) -> EAnimationProperty {
+        match self {
+            EAnimationCurve::Translation(_) => EAnimationProperty::Translation,
+            EAnimationCurve::Rotation(_) => EAnimationProperty::Rotation,
+            EAnimationCurve::Scaling(_) => EAnimationProperty::Scaling,
+            EAnimationCurve::Float(_) => EAnimationProperty::Float,
+        }
+    }
+
+    /// 关键帧时间范围, 无关键帧时返回 None
+    fn range(&self) -> Option<(Number, Number)> {
+        let (count, start, end) = match self {
+            EAnimationCurve::Translation(curve) | EAnimationCurve::Scaling(curve) => (curve.keys().len(), curve.start(), curve.end()),
+            EAnimationCurve::Rotation(curve) => (curve.keys().len(), curve.start(), curve.end()),
+            EAnimationCurve::Float(curve) => (curve.keys().len(), curve.start(), curve.end()),
+        };
+        if count == 0 {
+            None
+        } else {
+            Some((start, end))
+        }
+    }
+
+    /// 采样为 Vector4 - 位移 缩放取 xyz, 浮点取 x, 旋转取四元数分量
+    fn sample(&self, time: Number, cursor: &mut usize) -> Option<Vector4> {
+        match self {
+            EAnimationCurve::Translation(curve) | EAnimationCurve::Scaling(curve) => curve.sample_with_cursor(time, cursor).map(|value| value.push(0.)),
+            EAnimationCurve::Rotation(curve) => curve.sample_with_cursor(time, cursor).map(|value| value.normalize().coords),
+            EAnimationCurve::Float(curve) => curve.sample_with_cursor(time, cursor).map(|value| Vector4::new(value, 0., 0., 0.)),
+        }
+    }
+}
+
+/// 动画通道 - 曲线与目标绑定
+/// * 位移 旋转 缩放通道的 target 为变换数组下标, 浮点通道的 target 为浮点属性数组下标
+#[derive(Debug, Clone)]
+pub struct AnimationChannel {
+    pub target: usize,
+    pub curve: EAnimationCurve,
+}
+
+impl AnimationChannel {
+    pub fn new(target: usize, curve: EAnimationCurve) -> Self {
+        Self { target, curve }
+    }
+
+    pub fn property(&self) -> EAnimationProperty {
+        self.curve.property()
+    }
+}
+
+/// 动画片段 - 一组通道, 时间范围为所有通道关键帧范围的并集
+#[derive(Debug, Clone, Default)]
+pub struct AnimationClip {
+    pub name: String,
+    channels: Vec<AnimationChannel>,
+    range: Option<(Number, Number)>,
+}
+
+impl AnimationClip {
+    pub fn new(name: &str) -> Self {
+        Self { name: String::from(name), channels: Vec::new(), range: None }
+    }
+
+    pub fn add_channel(&mut self, channel: AnimationChannel) {
+        if let Some((start, end)) = channel.curve.range() {
+            self.range = Some(match self.range {
+                Some((min, max)) => (min.min(start), max.max(end)),
+                None => (start, end),
+            });
+        }
+        self.channels.push(channel);
+    }
+
+    pub fn channels(&self) -> &[AnimationChannel] {
+        &self.channels
+    }
+
+    pub fn start(&self) -> Number {
+        self.range.map(|range| range.0).unwrap_or(0.)
+    }
+
+    pub fn end(&self) -> Number {
+        self.range.map(|range| range.1).unwrap_or(0.)
+    }
+
+    pub fn duration(&self) -> Number {
+        self.end() - self.start()
+    }
+}
+
+/// 片段播放循环方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EAnimationLoop {
+    /// 播放到末尾停止, 保持末帧
+    Once,
+    #[default]
+    Loop,
+    /// 往返
+    PingPong,
+}
+
+#[derive(Debug, Clone)]
+struct AnimationState {
+    clip: usize,
+    /// 片段时间 - Loop 时位于 [start, end), PingPong 时位于 [start, start + 2 * duration)
+    time: Number,
+    speed: Number,
+    loop_mode: EAnimationLoop,
+    playing: bool,
+    /// 淡入淡出权重 [0, 1]
+    weight: Number,
+    /// 每秒权重变化量
+    fade_speed: Number,
+    /// 各通道采样游标
+    cursors: Vec<usize>,
+}
+
+impl AnimationState {
+    fn new(clip: &AnimationClip, index: usize, loop_mode: EAnimationLoop, weight: Number) -> Self {
+        Self {
+            clip: index,
+            time: clip.start(),
+            speed: 1.,
+            loop_mode,
+            playing: true,
+            weight,
+            fade_speed: 0.,
+            cursors: vec![0; clip.channels.len()],
+        }
+    }
+
+    fn advance(&mut self, clip: &AnimationClip, delta_time: Number) {
+        let start = clip.start();
+        let duration = clip.duration();
+        self.time += delta_time * self.speed;
+        if duration <= 0. {
+            self.time = start;
+            return;
+        }
+        match self.loop_mode {
+            EAnimationLoop::Once => {
+                let forward = delta_time * self.speed;
+                if (forward > 0. && self.time >= clip.end()) || (forward < 0. && self.time <= start) {
+                    self.playing = false;
+                }
+                self.time = self.time.clamp(start, clip.end());
+            },
+            EAnimationLoop::Loop => self.time = start + (self.time - start).rem_euclid(duration),
+            EAnimationLoop::PingPong => self.time = start + (self.time - start).rem_euclid(2. * duration),
+        }
+    }
+
+    /// 片段内采样时间
+    fn local_time(&self, clip: &AnimationClip) -> Number {
+        let offset = self.time - clip.start();
+        if self.loop_mode == EAnimationLoop::PingPong && offset > clip.duration() {
+            clip.start() + 2. * clip.duration() - offset
+        } else {
+            self.time
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AnimationLayer {
+    weight: Number,
+    /// 最后一个为当前状态, 其余为淡出中的状态
+    states: Vec<AnimationState>,
+}
+
+/// 同一目标属性的加权累积
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    value: Vector4,
+    weight: Number,
+    /// 旋转对齐半球的参考
+    reference: Vector4,
+}
+
+/// 动画播放器
+/// * 片段加入播放器后以下标引用; 层 0 默认存在, 权重为 1
+/// * 每层内各状态 (交叉淡入淡出) 按权重混合, 各层按顺序以层权重覆盖到目标上
+/// * 被驱动的属性先重置为基础姿态 (见 `set_rest_pose`), 权重和或层权重小于 1 时与下层结果插值, 结果与帧率无关
+/// * 每帧调用 `update` 推进时间, `apply` 将结果写入 Transform3 与浮点属性, 随后 `calc_matrix` 生效
+#[derive(Debug, Clone)]
+pub struct AnimationPlayer {
+    clips: Vec<AnimationClip>,
+    layers: Vec<AnimationLayer>,
+    accumulators: HashMap<(usize, EAnimationProperty), Accumulator>,
+    /// 基础姿态 (位移, 旋转, 缩放)
+    rest_transforms: Vec<(Vector3, Quaternion, Vector3)>,
+    rest_floats: Vec<Number>,
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        Self {
+            clips: Vec::new(),
+            layers: vec![AnimationLayer { weight: 1., states: Vec::new() }],
+            accumulators: HashMap::new(),
+            rest_transforms: Vec::new(),
+            rest_floats: Vec::new(),
+        }
+    }
+}
+
+impl AnimationPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加片段, 返回片段下标
+    pub fn add_clip(&mut self, clip: AnimationClip) -> usize {
+        self.clips.push(clip);
+        self.clips.len() - 1
+    }
+
+    pub fn clip(&self, index: usize) -> Option<&AnimationClip> {
+        self.clips.get(index)
+    }
+
+    /// 添加层, 返回层下标
+    pub fn add_layer(&mut self, weight: Number) -> usize {
+        self.layers.push(AnimationLayer { weight, states: Vec::new() });
+        self.layers.len() - 1
+    }
+
+    pub fn layer_weight(&self, layer: usize) -> Option<Number> {
+        self.layers.get(layer).map(|layer| layer.weight)
+    }
+
+    pub fn set_layer_weight(&mut self, layer: usize, weight: Number) -> bool {
+        match self.layers.get_mut(layer) {
+            Some(layer) => {
+                layer.weight = weight;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// 记录混合的基础姿态, 通常为绑定姿态
+    /// * 未记录的目标以单位变换与 0 为基础
+    pub fn set_rest_pose(&mut self, transforms: &[Transform3], floats: &[Number]) {
+        self.rest_transforms.clear();
+        self.rest_transforms.extend(transforms.iter().map(|transform| (transform.translation(), transform.rotation_quaternion(), transform.scaling())));
+        self.rest_floats.clear();
+        self.rest_floats.extend_from_slice(floats);
+    }
+
+    /// 在层上立即播放片段, 替换该层所有状态
+    pub fn play(&mut self, layer: usize, clip: usize, loop_mode: EAnimationLoop) -> bool {
+        let (Some(layer), Some(clip_data)) = (self.layers.get_mut(layer), self.clips.get(clip)) else {
+            return false;
+        };
+        layer.states.clear();
+        layer.states.push(AnimationState::new(clip_data, clip, loop_mode, 1.));
+        true
+    }
+
+    /// 在 duration 秒内从层上当前状态交叉淡入到片段
+    pub fn cross_fade(&mut self, layer: usize, clip: usize, loop_mode: EAnimationLoop, duration: Number) -> bool {
+        if duration <= 0. {
+            return self.play(layer, clip, loop_mode);
+        }
+        let (Some(layer), Some(clip_data)) = (self.layers.get_mut(layer), self.clips.get(clip)) else {
+            return false;
+        };
+        // 尚未淡入的状态直接移除, 否则淡出速度为 -0 而永远留在层上
+        layer.states.retain(|state| state.weight > 0.);
+        for state in layer.states.iter_mut() {
+            state.fade_speed = -state.weight / duration;
+        }
+        let mut state = AnimationState::new(clip_data, clip, loop_mode, 0.);
+        state.fade_speed = 1. / duration;
+        layer.states.push(state);
+        true
+    }
+
+    /// 停止层上所有状态
+    pub fn stop(&mut self, layer: usize) {
+        if let Some(layer) = self.layers.get_mut(layer) {
+            layer.states.clear();
+        }
+    }
+
+    pub fn pause(&mut self, layer: usize) {
+        if let Some(state) = self.current_mut(layer) {
+            state.playing = false;
+        }
+    }
+
+    pub fn resume(&mut self, layer: usize) {
+        if let Some(state) = self.current_mut(layer) {
+            state.playing = true;
+        }
+    }
+
+    pub fn is_playing(&self, layer: usize) -> bool {
+        self.current(layer).map(|state| state.playing).unwrap_or(false)
+    }
+
+    /// 跳转当前状态到片段时间
+    pub fn seek(&mut self, layer: usize, time: Number) {
+        if let Some(state) = self.layers.get_mut(layer).and_then(|layer| layer.states.last_mut()) {
+            state.time = time;
+            state.advance(&self.clips[state.clip], 0.);
+        }
+    }
+
+    /// 当前状态的片段内采样时间
+    pub fn time(&self, layer: usize) -> Option<Number> {
+        self.current(layer).map(|state| state.local_time(&self.clips[state.clip]))
+    }
+
+    /// 当前状态播放速度, 负值倒放
+    pub fn set_speed(&mut self, layer: usize, speed: Number) {
+        if let Some(state) = self.current_mut(layer) {
+            state.speed = speed;
+        }
+    }
+
+    /// 推进播放时间与淡入淡出
+    pub fn update(&mut self, delta_time: Number) {
+        for layer in self.layers.iter_mut() {
+            for state in layer.states.iter_mut() {
+                if state.playing {
+                    state.advance(&self.clips[state.clip], delta_time);
+                }
+                if state.fade_speed != 0. {
+                    state.weight = (state.weight + state.fade_speed * delta_time).clamp(0., 1.);
+                    if state.weight >= 1. {
+                        state.fade_speed = 0.;
+                    }
+                }
+            }
+            layer.states.retain(|state| state.fade_speed >= 0. || state.weight > 0.);
+        }
+    }
+
+    /// 采样并写入目标, 越界的 target 忽略
+    pub fn apply(&mut self, transforms: &mut [Transform3], floats: &mut [Number]) {
+        // 本帧被驱动的属性先回到基础姿态
+        for layer in self.layers.iter().filter(|layer| layer.weight > 0.) {
+            for state in layer.states.iter().filter(|state| state.weight > 0.) {
+                for channel in self.clips[state.clip].channels.iter() {
+                    self.reset(channel.target, channel.property(), transforms, floats);
+                }
+            }
+        }
+
+        for layer in self.layers.iter_mut() {
+            if layer.weight <= 0. {
+                continue;
+            }
+
+            self.accumulators.clear();
+            for state in layer.states.iter_mut() {
+                if state.weight <= 0. {
+                    continue;
+                }
+                let clip = &self.clips[state.clip];
+                let time = state.local_time(clip);
+                for (channel, cursor) in clip.channels.iter().zip(state.cursors.iter_mut()) {
+                    let Some(value) = channel.curve.sample(time, cursor) else {
+                        continue;
+                    };
+                    let accumulator = self.accumulators.entry((channel.target, channel.property())).or_insert(Accumulator {
+                        value: Vector4::zeros(),
+                        weight: 0.,
+                        reference: value,
+                    });
+                    let sign = if channel.property() == EAnimationProperty::Rotation && value.dot(&accumulator.reference) < 0. { -1. } else { 1. };
+                    accumulator.value += value * (state.weight * sign);
+                    accumulator.weight += state.weight;
+                }
+            }
+
+            for ((target, property), accumulator) in self.accumulators.iter() {
+                let amount = accumulator.weight.min(1.) * layer.weight;
+                let value = accumulator.value / accumulator.weight;
+                match property {
+                    EAnimationProperty::Translation => if let Some(transform) = transforms.get_mut(*target) {
+                        transform.translation_mut(&transform.translation().lerp(&value.xyz(), amount));
+                    },
+                    EAnimationProperty::Scaling => if let Some(transform) = transforms.get_mut(*target) {
+                        transform.scaling_mut(&transform.scaling().lerp(&value.xyz(), amount));
+                    },
+                    EAnimationProperty::Rotation => if let Some(transform) = transforms.get_mut(*target) {
+                        let mut rotation = Quaternion::identity();
+                        CoordinateSytem3::quaternion_slerp(&transform.rotation_quaternion(), &Quaternion::new_normalize(SQuaternion::from(value)), amount, &mut rotation);
+                        transform.rotation_quaternion_mut(&rotation);
+                    },
+                    EAnimationProperty::Float => if let Some(float) = floats.get_mut(*target) {
+                        *float += (value.x - *float) * amount;
+                    },
+                }
+            }
+        }
+    }
+
+    fn reset(&self, target: usize, property: EAnimationProperty, transforms: &mut [Transform3], floats: &mut [Number]) {
+        let rest = self.rest_transforms.get(target);
+        match property {
+            EAnimationProperty::Translation => if let Some(transform) = transforms.get_mut(target) {
+                transform.translation_mut(&rest.map(|rest| rest.0).unwrap_or_else(Vector3::zeros));
+            },
+            EAnimationProperty::Rotation => if let Some(transform) = transforms.get_mut(target) {
+                transform.rotation_quaternion_mut(&rest.map(|rest| rest.1).unwrap_or_else(Quaternion::identity));
+            },
+            EAnimationProperty::Scaling => if let Some(transform) = transforms.get_mut(target) {
+                transform.scaling_mut(&rest.map(|rest| rest.2).unwrap_or_else(|| Vector3::repeat(1.)));
+            },
+            EAnimationProperty::Float => if let Some(float) = floats.get_mut(target) {
+                *float = self.rest_floats.get(target).copied().unwrap_or(0.);
+            },
+        }
+    }
+
+    fn current(&self, layer: usize) -> Option<&AnimationState> {
+        self.layers.get(layer).and_then(|layer| layer.states.last())
+    }
+
+    fn current_mut(&mut self, layer: usize) -> Option<&mut AnimationState> {
+        self.layers.get_mut(layer).and_then(|layer| layer.states.last_mut())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Quaternion, SQuaternion, transform::Transform3, curve::{FrameCurve, KeyFrame}, consts::FRAC_PI_2};
+
+    use super::{AnimationPlayer, AnimationClip, AnimationChannel, EAnimationCurve, EAnimationLoop};
+
+    fn about_y(angle: Number) -> SQuaternion<Number> {
+        Quaternion::from_axis_angle(&Vector3::y_axis(), angle).into_inner()
+    }
+
+    fn translation_clip(from: Number, to: Number) -> AnimationClip {
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::linear(0., Vector3::new(from, 0., 0.)));
+        curve.add_key(KeyFrame::linear(1., Vector3::new(to, 0., 0.)));
+        let mut clip = AnimationClip::new("move");
+        clip.add_channel(AnimationChannel::new(0, EAnimationCurve::Translation(curve)));
+        clip
+    }
+
+    #[test]
+    fn test_animation_play() {
+        let mut clip = translation_clip(0., 10.);
+        let mut rotation = FrameCurve::new();
+        rotation.add_key(KeyFrame::linear(0., about_y(0.)));
+        rotation.add_key(KeyFrame::linear(2., about_y(FRAC_PI_2)));
+        clip.add_channel(AnimationChannel::new(0, EAnimationCurve::Rotation(rotation)));
+        let mut float = FrameCurve::new();
+        float.add_key(KeyFrame::linear(0., 1.));
+        float.add_key(KeyFrame::linear(2., 0.));
+        clip.add_channel(AnimationChannel::new(1, EAnimationCurve::Float(float)));
+        assert_relative_eq!(clip.duration(), 2.);
+
+        let mut player = AnimationPlayer::new();
+        let clip = player.add_clip(clip);
+        let mut transforms = vec![Transform3::default()];
+        let mut floats = vec![0., 0.];
+        assert!(player.play(0, clip, EAnimationLoop::Once));
+        player.update(0.5);
+        player.apply(&mut transforms, &mut floats);
+        transforms[0].calc_matrix();
+        assert_relative_eq!(transforms[0].matrix().fixed_view::<3, 1>(0, 3).into_owned(), Vector3::new(5., 0., 0.), epsilon = 0.0001);
+        assert_relative_eq!(transforms[0].rotation_quaternion().into_inner(), about_y(FRAC_PI_2 * 0.25), epsilon = 0.0001);
+        assert_relative_eq!(floats[1], 0.75, epsilon = 0.0001);
+
+        // 暂停 速度 跳转
+        player.pause(0);
+        player.update(0.5);
+        assert_relative_eq!(player.time(0).unwrap(), 0.5);
+        player.resume(0);
+        player.set_speed(0, 2.);
+        player.update(0.25);
+        assert_relative_eq!(player.time(0).unwrap(), 1.);
+        player.seek(0, 1.5);
+        assert_relative_eq!(player.time(0).unwrap(), 1.5);
+
+        // Once 播放到末尾停止
+        player.update(1.);
+        assert!(!player.is_playing(0));
+        assert_relative_eq!(player.time(0).unwrap(), 2.);
+        player.apply(&mut transforms, &mut floats);
+        assert_relative_eq!(transforms[0].translation(), Vector3::new(10., 0., 0.), epsilon = 0.0001);
+
+        // 循环与往返
+        player.play(0, clip, EAnimationLoop::Loop);
+        player.update(2.5);
+        assert_relative_eq!(player.time(0).unwrap(), 0.5, epsilon = 0.0001);
+        player.play(0, clip, EAnimationLoop::PingPong);
+        player.update(2.5);
+        assert_relative_eq!(player.time(0).unwrap(), 1.5, epsilon = 0.0001);
+        assert!(player.is_playing(0));
+    }
+
+    #[test]
+    fn test_animation_blend() {
+        let mut player = AnimationPlayer::new();
+        let idle = player.add_clip(translation_clip(0., 0.));
+        let walk = player.add_clip(translation_clip(10., 10.));
+        let wave = player.add_clip(translation_clip(20., 20.));
+        let mut transforms = vec![Transform3::default()];
+
+        // 交叉淡入
+        player.play(0, idle, EAnimationLoop::Loop);
+        player.cross_fade(0, walk, EAnimationLoop::Loop, 1.);
+        player.update(0.25);
+        player.apply(&mut transforms, &mut []);
+        assert_relative_eq!(transforms[0].translation().x, 2.5, epsilon = 0.0001);
+        player.update(1.);
+        player.apply(&mut transforms, &mut []);
+        assert_relative_eq!(transforms[0].translation().x, 10., epsilon = 0.0001);
+
+        // 上层按层权重覆盖
+        let upper = player.add_layer(0.5);
+        player.play(upper, wave, EAnimationLoop::Loop);
+        player.update(0.1);
+        player.apply(&mut transforms, &mut []);
+        assert_relative_eq!(transforms[0].translation().x, 15., epsilon = 0.0001);
+        assert!(player.set_layer_weight(upper, 0.));
+        player.apply(&mut transforms, &mut []);
+        assert_relative_eq!(transforms[0].translation().x, 10., epsilon = 0.0001);
+
+        // 同一帧内重复交叉淡入, 未开始淡入的状态不残留
+        player.stop(0);
+        player.play(0, idle, EAnimationLoop::Loop);
+        player.cross_fade(0, walk, EAnimationLoop::Loop, 1.);
+        player.cross_fade(0, walk, EAnimationLoop::Loop, 1.);
+        assert_eq!(player.layers[0].states.len(), 2);
+        player.update(2.);
+        assert_eq!(player.layers[0].states.len(), 1);
+
+        // 无效层或片段
+        assert!(!player.play(5, walk, EAnimationLoop::Loop));
+        assert!(!player.cross_fade(0, 7, EAnimationLoop::Loop, 1.));
+    }
+
+    #[test]
+    fn test_animation_rest_pose() {
+        let mut player = AnimationPlayer::new();
+        let wave = player.add_clip(translation_clip(20., 20.));
+        let mut float = FrameCurve::new();
+        float.add_key(KeyFrame::linear(0., 1.));
+        let mut fade = AnimationClip::new("fade");
+        fade.add_channel(AnimationChannel::new(0, EAnimationCurve::Float(float)));
+        let fade = player.add_clip(fade);
+
+        let mut transforms = vec![Transform3::default()];
+        transforms[0].set_translation_from_floats(4., 0., 0.);
+        let mut floats = vec![0.5];
+        player.set_rest_pose(&transforms, &floats);
+
+        // 下层未驱动的属性以层权重与基础姿态混合, 重复调用结果不变
+        let upper = player.add_layer(0.5);
+        player.play(upper, wave, EAnimationLoop::Loop);
+        player.play(0, fade, EAnimationLoop::Loop);
+        player.set_layer_weight(0, 0.5);
+        player.update(0.1);
+        for _ in 0..3 {
+            player.apply(&mut transforms, &mut floats);
+            assert_relative_eq!(transforms[0].translation().x, 12., epsilon = 0.0001);
+            assert_relative_eq!(floats[0], 0.75, epsilon = 0.0001);
+        }
+    }
+}
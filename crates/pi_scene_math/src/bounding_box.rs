@@ -0,0 +1,256 @@
+use crate::{Number, Vector3, Matrix, coordiante_system::CoordinateSytem3, vector::{TMinimizeMaximize, TToolVector3}, frustum::{FrustumPlanes, EContainment}};
+
+/// 轴对齐包围盒
+/// * 局部空间数据 `minimum` `maximum` `center` `extend_size` `vectors`
+/// * 世界空间数据 由 `update` 根据世界矩阵重新计算
+#[derive(Debug, Clone)]
+pub struct BoundingBox {
+    /// 局部空间最小点
+    pub minimum: Vector3,
+    /// 局部空间最大点
+    pub maximum: Vector3,
+    /// 局部空间中心
+    pub center: Vector3,
+    /// 局部空间半尺寸
+    pub extend_size: Vector3,
+    /// 局部空间 8 个角点
+    pub vectors: [Vector3; 8],
+    /// 世界空间最小点
+    pub minimum_world: Vector3,
+    /// 世界空间最大点
+    pub maximum_world: Vector3,
+    /// 世界空间中心
+    pub center_world: Vector3,
+    /// 世界空间轴对齐半尺寸
+    pub extend_size_world: Vector3,
+    /// 世界空间 8 个角点
+    pub vectors_world: [Vector3; 8],
+    /// 世界空间下包围盒三个单位方向轴
+    pub directions: [Vector3; 3],
+    /// 沿 directions 的半长 - 即有向包围盒的半尺寸
+    pub extend_size_oriented: Vector3,
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::new(&Vector3::zeros(), &Vector3::zeros(), None)
+    }
+}
+
+impl BoundingBox {
+    pub fn new(minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) -> Self {
+        let mut result = Self {
+            minimum: Vector3::zeros(),
+            maximum: Vector3::zeros(),
+            center: Vector3::zeros(),
+            extend_size: Vector3::zeros(),
+            vectors: [Vector3::zeros(); 8],
+            minimum_world: Vector3::zeros(),
+            maximum_world: Vector3::zeros(),
+            center_world: Vector3::zeros(),
+            extend_size_world: Vector3::zeros(),
+            vectors_world: [Vector3::zeros(); 8],
+            directions: [Vector3::x(), Vector3::y(), Vector3::z()],
+            extend_size_oriented: Vector3::zeros(),
+        };
+        result.reconstruct(minimum, maximum, world);
+        result
+    }
+
+    /// 包围点集, 点集为空时返回零尺寸包围盒
+    pub fn from_points(points: &[Vector3], world: Option<&Matrix>) -> Self {
+        let mut minimum = Vector3::zeros();
+        let mut maximum = Vector3::zeros();
+        extents_of_points(points, &mut minimum, &mut maximum);
+        Self::new(&minimum, &maximum, world)
+    }
+
+    /// 重设局部空间范围, 并以 world (缺省为单位矩阵) 更新世界空间数据
+    pub fn reconstruct(&mut self, minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) {
+        let min_x = minimum.x; let min_y = minimum.y; let min_z = minimum.z;
+        let max_x = maximum.x; let max_y = maximum.y; let max_z = maximum.z;
+
+        self.minimum.copy_from(minimum);
+        self.maximum.copy_from(maximum);
+
+        self.vectors[0].copy_from_slice(&[min_x, min_y, min_z]);
+        self.vectors[1].copy_from_slice(&[max_x, max_y, max_z]);
+        self.vectors[2].copy_from_slice(&[max_x, min_y, min_z]);
+        self.vectors[3].copy_from_slice(&[min_x, max_y, min_z]);
+        self.vectors[4].copy_from_slice(&[min_x, min_y, max_z]);
+        self.vectors[5].copy_from_slice(&[max_x, max_y, min_z]);
+        self.vectors[6].copy_from_slice(&[min_x, max_y, max_z]);
+        self.vectors[7].copy_from_slice(&[max_x, min_y, max_z]);
+
+        self.center.copy_from(&((maximum + minimum) * 0.5));
+        self.extend_size.copy_from(&((maximum - minimum) * 0.5));
+
+        match world {
+            Some(world) => self.update(world),
+            None => self.update(&Matrix::identity()),
+        }
+    }
+
+    /// 以世界矩阵重新计算世界空间数据
+    pub fn update(&mut self, world: &Matrix) {
+        self.minimum_world.fill(Number::MAX);
+        self.maximum_world.fill(-Number::MAX);
+
+        for i in 0..8 {
+            let v = &mut self.vectors_world[i];
+            CoordinateSytem3::transform_coordinates(&self.vectors[i], world, v);
+            self.minimum_world.minimize_mut(v);
+            self.maximum_world.maximize_mut(v);
+        }
+
+        self.extend_size_world.copy_from(&((self.maximum_world - self.minimum_world) * 0.5));
+        self.center_world.copy_from(&((self.maximum_world + self.minimum_world) * 0.5));
+
+        for i in 0..3 {
+            let axis = world.fixed_view::<3, 1>(0, i).into_owned();
+            let length = axis.norm();
+            if length > 0. {
+                self.directions[i].copy_from(&(axis / length));
+            } else {
+                self.directions[i].fill(0.);
+                self.directions[i][i] = 1.;
+            }
+            self.extend_size_oriented[i] = self.extend_size[i] * length;
+        }
+    }
+
+    /// 按比例缩放局部范围 (以局部中心为基准)
+    pub fn scale(&mut self, factor: Number, world: Option<&Matrix>) {
+        let diff = (self.maximum - self.minimum) * (0.5 * factor);
+        let minimum = self.center - diff;
+        let maximum = self.center + diff;
+        self.reconstruct(&minimum, &maximum, world);
+    }
+
+    /// 扩展局部范围以包含另一个包围盒的局部范围
+    pub fn merge(&mut self, other: &BoundingBox, world: Option<&Matrix>) {
+        self.merge_min_max(&other.minimum, &other.maximum, world);
+    }
+
+    /// 扩展局部范围以包含给定范围
+    pub fn merge_min_max(&mut self, minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) {
+        let mut new_minimum = self.minimum;
+        let mut new_maximum = self.maximum;
+        new_minimum.minimize_mut(minimum);
+        new_maximum.maximize_mut(maximum);
+        self.reconstruct(&new_minimum, &new_maximum, world);
+    }
+
+    /// 扩展局部范围以包含给定点
+    pub fn merge_point(&mut self, point: &Vector3, world: Option<&Matrix>) {
+        self.merge_min_max(point, point, world);
+    }
+
+    /// 世界空间点是否在包围盒内
+    pub fn intersects_point(&self, point: &Vector3) -> bool {
+        Self::intersects_point_min_max(&self.minimum_world, &self.maximum_world, point)
+    }
+
+    /// 世界空间球是否与包围盒相交
+    pub fn intersects_sphere(&self, center: &Vector3, radius: Number) -> bool {
+        Self::intersects_sphere_min_max(&self.minimum_world, &self.maximum_world, center, radius)
+    }
+
+    /// 世界空间轴对齐范围是否与包围盒相交
+    pub fn intersects_min_max(&self, minimum: &Vector3, maximum: &Vector3) -> bool {
+        Self::intersects_min_max_min_max(&self.minimum_world, &self.maximum_world, minimum, maximum)
+    }
+
+    /// 两个包围盒世界空间范围是否相交
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.intersects_min_max(&other.minimum_world, &other.maximum_world)
+    }
+
+    /// 世界空间轴对齐范围与视锥的关系
+    pub fn is_in_frustum(&self, frustum: &FrustumPlanes) -> EContainment {
+        frustum.intersects_aabb(&self.minimum_world, &self.maximum_world)
+    }
+
+    /// 有向包围盒与视锥的关系 - 精确测试
+    pub fn is_in_frustum_exact(&self, frustum: &FrustumPlanes) -> EContainment {
+        frustum.intersects_obb_exact(&self.center_world, &self.directions, &self.extend_size_oriented)
+    }
+
+    pub fn intersects_point_min_max(minimum: &Vector3, maximum: &Vector3, point: &Vector3) -> bool {
+        minimum.x <= point.x && point.x <= maximum.x
+            && minimum.y <= point.y && point.y <= maximum.y
+            && minimum.z <= point.z && point.z <= maximum.z
+    }
+
+    pub fn intersects_sphere_min_max(minimum: &Vector3, maximum: &Vector3, center: &Vector3, radius: Number) -> bool {
+        let mut clamped = Vector3::zeros();
+        CoordinateSytem3::clamp(center, minimum, maximum, &mut clamped);
+        CoordinateSytem3::distance_squared(center, &clamped) <= radius * radius
+    }
+
+    pub fn intersects_min_max_min_max(minimum0: &Vector3, maximum0: &Vector3, minimum1: &Vector3, maximum1: &Vector3) -> bool {
+        minimum0.x <= maximum1.x && maximum0.x >= minimum1.x
+            && minimum0.y <= maximum1.y && maximum0.y >= minimum1.y
+            && minimum0.z <= maximum1.z && maximum0.z >= minimum1.z
+    }
+}
+
+/// 点集的分量最小最大值, 点集为空时为零
+pub fn extents_of_points(points: &[Vector3], minimum: &mut Vector3, maximum: &mut Vector3) {
+    if points.is_empty() {
+        minimum.fill(0.);
+        maximum.fill(0.);
+        return;
+    }
+    minimum.fill(Number::MAX);
+    maximum.fill(-Number::MAX);
+    for point in points {
+        minimum.minimize_mut(point);
+        maximum.maximize_mut(point);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Matrix, Rotation3};
+
+    use super::BoundingBox;
+
+    #[test]
+    fn test_bounding_box_points() {
+        let points = [Vector3::new(1., -2., 0.), Vector3::new(-1., 4., 2.), Vector3::new(0., 0., -2.)];
+        let bounding = BoundingBox::from_points(&points, None);
+        assert_relative_eq!(bounding.minimum, Vector3::new(-1., -2., -2.));
+        assert_relative_eq!(bounding.maximum, Vector3::new(1., 4., 2.));
+        assert_relative_eq!(bounding.center, Vector3::new(0., 1., 0.));
+        assert_relative_eq!(bounding.extend_size, Vector3::new(1., 3., 2.));
+        assert_relative_eq!(bounding.minimum_world, bounding.minimum);
+        assert!(bounding.intersects_point(&Vector3::new(0.5, 3.9, 1.)));
+        assert!(!bounding.intersects_point(&Vector3::new(0.5, 4.1, 1.)));
+
+        let mut merged = BoundingBox::new(&Vector3::new(0., 0., 0.), &Vector3::new(1., 1., 1.), None);
+        merged.merge(&BoundingBox::new(&Vector3::new(2., -1., 0.), &Vector3::new(3., 0., 1.), None), None);
+        assert_relative_eq!(merged.minimum, Vector3::new(0., -1., 0.));
+        assert_relative_eq!(merged.maximum, Vector3::new(3., 1., 1.));
+    }
+
+    #[test]
+    fn test_bounding_box_world() {
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), crate::consts::FRAC_PI_4);
+        let mut world: Matrix = rotation.to_homogeneous();
+        world.prepend_nonuniform_scaling_mut(&Vector3::new(2., 1., 1.));
+        world.append_translation_mut(&Vector3::new(10., 0., 0.));
+
+        let bounding = BoundingBox::new(&Vector3::new(-1., -1., -1.), &Vector3::new(1., 1., 1.), Some(&world));
+        let half = 3. * crate::consts::FRAC_1_SQRT_2;
+        assert_relative_eq!(bounding.center_world, Vector3::new(10., 0., 0.), epsilon = 0.0001);
+        assert_relative_eq!(bounding.extend_size_world, Vector3::new(half, half, 1.), epsilon = 0.0001);
+        assert_relative_eq!(bounding.extend_size_oriented, Vector3::new(2., 1., 1.), epsilon = 0.0001);
+        assert_relative_eq!(bounding.directions[0], rotation * Vector3::x(), epsilon = 0.0001);
+
+        assert!(bounding.intersects_sphere(&Vector3::new(10. + half + 0.5, 0., 0.), 0.6));
+        assert!(!bounding.intersects_sphere(&Vector3::new(10. + half + 0.5, 0., 0.), 0.4));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{Number, Vector3, Matrix, bounding_box::{BoundingBox, extents_of_points}, bounding_sphere::BoundingSphere, frustum::{FrustumPlanes, EContainment}};
+
+/// 包围信息 - 包围盒 + 包围球
+/// * 两者由同一局部范围构建, 剔除时先用包围球粗测再用包围盒细测
+#[derive(Debug, Clone, Default)]
+pub struct BoundingInfo {
+    bounding_box: BoundingBox,
+    bounding_sphere: BoundingSphere,
+    /// 锁定后 `update` 不再刷新世界空间数据
+    pub is_locked: bool,
+}
+
+impl BoundingInfo {
+    pub fn new(minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) -> Self {
+        Self {
+            bounding_box: BoundingBox::new(minimum, maximum, world),
+            bounding_sphere: BoundingSphere::new(minimum, maximum, world),
+            is_locked: false,
+        }
+    }
+
+    /// 包围点集
+    pub fn from_points(points: &[Vector3], world: Option<&Matrix>) -> Self {
+        let mut minimum = Vector3::zeros();
+        let mut maximum = Vector3::zeros();
+        extents_of_points(points, &mut minimum, &mut maximum);
+        Self::new(&minimum, &maximum, world)
+    }
+
+    pub fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+
+    pub fn bounding_sphere(&self) -> &BoundingSphere {
+        &self.bounding_sphere
+    }
+
+    /// 局部空间最小点
+    pub fn minimum(&self) -> &Vector3 {
+        &self.bounding_box.minimum
+    }
+
+    /// 局部空间最大点
+    pub fn maximum(&self) -> &Vector3 {
+        &self.bounding_box.maximum
+    }
+
+    /// 世界空间包围盒对角线长度
+    pub fn diagonal_length(&self) -> Number {
+        (self.bounding_box.maximum_world - self.bounding_box.minimum_world).norm()
+    }
+
+    /// 重设局部范围
+    pub fn reconstruct(&mut self, minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) {
+        self.bounding_box.reconstruct(minimum, maximum, world);
+        self.bounding_sphere.reconstruct(minimum, maximum, world);
+    }
+
+    /// 以世界矩阵更新世界空间数据, 锁定时忽略
+    pub fn update(&mut self, world: &Matrix) {
+        if self.is_locked {
+            return;
+        }
+        self.bounding_box.update(world);
+        self.bounding_sphere.update(world);
+    }
+
+    /// 以中心和半尺寸重设局部范围
+    pub fn center_on(&mut self, center: &Vector3, extend: &Vector3, world: Option<&Matrix>) {
+        let minimum = center - extend;
+        let maximum = center + extend;
+        self.reconstruct(&minimum, &maximum, world);
+    }
+
+    /// 扩展局部范围以包含点
+    pub fn encapsulate(&mut self, point: &Vector3, world: Option<&Matrix>) {
+        self.bounding_box.merge_point(point, world);
+        let minimum = self.bounding_box.minimum;
+        let maximum = self.bounding_box.maximum;
+        self.bounding_sphere.reconstruct(&minimum, &maximum, world);
+    }
+
+    /// 扩展局部范围以包含另一个包围信息的局部范围
+    pub fn encapsulate_bounding_info(&mut self, other: &BoundingInfo, world: Option<&Matrix>) {
+        self.bounding_box.merge(&other.bounding_box, world);
+        let minimum = self.bounding_box.minimum;
+        let maximum = self.bounding_box.maximum;
+        self.bounding_sphere.reconstruct(&minimum, &maximum, world);
+    }
+
+    /// 按比例缩放局部范围
+    pub fn scale(&mut self, factor: Number, world: Option<&Matrix>) {
+        self.bounding_box.scale(factor, world);
+        let minimum = self.bounding_box.minimum;
+        let maximum = self.bounding_box.maximum;
+        self.bounding_sphere.reconstruct(&minimum, &maximum, world);
+    }
+
+    /// 与视锥的关系 - 包围球粗测 + 世界空间轴对齐包围盒
+    pub fn is_in_frustum(&self, frustum: &FrustumPlanes) -> EContainment {
+        let result = self.bounding_sphere.is_in_frustum(frustum);
+        if result != EContainment::Intersecting {
+            return result;
+        }
+        self.bounding_box.is_in_frustum(frustum)
+    }
+
+    /// 与视锥的关系 - 包围球粗测 + 有向包围盒精确测试
+    pub fn is_in_frustum_exact(&self, frustum: &FrustumPlanes) -> EContainment {
+        let result = self.bounding_sphere.is_in_frustum(frustum);
+        if result != EContainment::Intersecting {
+            return result;
+        }
+        self.bounding_box.is_in_frustum_exact(frustum)
+    }
+
+    /// 世界空间点是否在包围范围内
+    pub fn intersects_point(&self, point: &Vector3) -> bool {
+        self.bounding_sphere.intersects_point(point) && self.bounding_box.intersects_point(point)
+    }
+
+    /// 与另一个包围信息是否相交
+    pub fn intersects(&self, other: &BoundingInfo) -> bool {
+        self.bounding_sphere.intersects(&other.bounding_sphere) && self.bounding_box.intersects(&other.bounding_box)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Matrix, frustum::{FrustumPlanes, EContainment}, camera::EDepthRange, Perspective3};
+
+    use super::BoundingInfo;
+
+    #[test]
+    fn test_bounding_info() {
+        let mut info = BoundingInfo::new(&Vector3::new(-1., -1., -1.), &Vector3::new(1., 1., 1.), None);
+        info.encapsulate(&Vector3::new(3., 0., 0.), None);
+        assert_relative_eq!(*info.maximum(), Vector3::new(3., 1., 1.));
+        assert_relative_eq!(info.bounding_sphere().center, Vector3::new(1., 0., 0.));
+
+        let mut world = Matrix::identity();
+        world.append_translation_mut(&Vector3::new(0., 0., -10.));
+        info.update(&world);
+        assert!(info.intersects_point(&Vector3::new(2.5, 0., -10.)));
+
+        let mut frustum = FrustumPlanes::default();
+        frustum.from_transform_matrix(&Perspective3::new(1., crate::consts::FRAC_PI_2, 1., 100.).to_homogeneous(), EDepthRange::NegativeOneToOne);
+        assert_eq!(info.is_in_frustum(&frustum), EContainment::Inside);
+        assert_eq!(info.is_in_frustum_exact(&frustum), EContainment::Inside);
+
+        info.is_locked = true;
+        world.append_translation_mut(&Vector3::new(0., 0., 20.));
+        info.update(&world);
+        assert_eq!(info.is_in_frustum(&frustum), EContainment::Inside);
+
+        info.is_locked = false;
+        info.update(&world);
+        assert_eq!(info.is_in_frustum(&frustum), EContainment::Outside);
+    }
+}
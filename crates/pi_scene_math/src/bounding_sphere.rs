@@ -0,0 +1,163 @@
+use crate::{Number, Vector3, Matrix, coordiante_system::CoordinateSytem3, vector::TToolVector3, bounding_box::extents_of_points, frustum::{FrustumPlanes, EContainment}};
+
+/// 包围球
+/// * 局部空间数据 `center` `radius` `minimum` `maximum`
+/// * 世界空间数据 由 `update` 根据世界矩阵重新计算
+#[derive(Debug, Clone)]
+pub struct BoundingSphere {
+    /// 局部空间球心
+    pub center: Vector3,
+    /// 局部空间半径
+    pub radius: Number,
+    /// 局部空间最小点
+    pub minimum: Vector3,
+    /// 局部空间最大点
+    pub maximum: Vector3,
+    /// 世界空间球心
+    pub center_world: Vector3,
+    /// 世界空间半径 - 按世界矩阵最大轴缩放
+    pub radius_world: Number,
+}
+
+impl Default for BoundingSphere {
+    fn default() -> Self {
+        Self::new(&Vector3::zeros(), &Vector3::zeros(), None)
+    }
+}
+
+impl BoundingSphere {
+    /// 包围给定范围的球
+    pub fn new(minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) -> Self {
+        let mut result = Self {
+            center: Vector3::zeros(),
+            radius: 0.,
+            minimum: Vector3::zeros(),
+            maximum: Vector3::zeros(),
+            center_world: Vector3::zeros(),
+            radius_world: 0.,
+        };
+        result.reconstruct(minimum, maximum, world);
+        result
+    }
+
+    /// 以球心半径创建
+    pub fn from_center_and_radius(center: &Vector3, radius: Number, world: Option<&Matrix>) -> Self {
+        let offset = Vector3::new(radius, radius, radius);
+        let mut result = Self::new(&(center - offset), &(center + offset), None);
+        result.center.copy_from(center);
+        result.radius = radius;
+        match world {
+            Some(world) => result.update(world),
+            None => result.update(&Matrix::identity()),
+        }
+        result
+    }
+
+    /// 包围点集 - 以点集包围盒中心为球心, 最远点距离为半径
+    pub fn from_points(points: &[Vector3], world: Option<&Matrix>) -> Self {
+        let mut minimum = Vector3::zeros();
+        let mut maximum = Vector3::zeros();
+        extents_of_points(points, &mut minimum, &mut maximum);
+
+        let center = (minimum + maximum) * 0.5;
+        let mut radius_squared: Number = 0.;
+        for point in points {
+            radius_squared = radius_squared.max(CoordinateSytem3::distance_squared(&center, point));
+        }
+        Self::from_center_and_radius(&center, radius_squared.sqrt(), world)
+    }
+
+    /// 重设为包围给定范围的球, 并以 world (缺省为单位矩阵) 更新世界空间数据
+    pub fn reconstruct(&mut self, minimum: &Vector3, maximum: &Vector3, world: Option<&Matrix>) {
+        self.minimum.copy_from(minimum);
+        self.maximum.copy_from(maximum);
+
+        self.center.copy_from(&((minimum + maximum) * 0.5));
+        self.radius = CoordinateSytem3::distance(minimum, maximum) * 0.5;
+
+        match world {
+            Some(world) => self.update(world),
+            None => self.update(&Matrix::identity()),
+        }
+    }
+
+    /// 以世界矩阵重新计算世界空间数据
+    pub fn update(&mut self, world: &Matrix) {
+        CoordinateSytem3::transform_coordinates(&self.center, world, &mut self.center_world);
+
+        let mut scale: Number = 0.;
+        for i in 0..3 {
+            scale = scale.max(world.fixed_view::<3, 1>(0, i).norm());
+        }
+        self.radius_world = self.radius * scale;
+    }
+
+    /// 按比例缩放局部半径
+    pub fn scale(&mut self, factor: Number, world: Option<&Matrix>) {
+        let center = self.center;
+        let radius = self.radius * factor;
+        *self = Self::from_center_and_radius(&center, radius, world);
+    }
+
+    /// 扩展为同时包含另一个球的局部空间最小球
+    pub fn merge(&mut self, other: &BoundingSphere, world: Option<&Matrix>) {
+        let offset = other.center - self.center;
+        let distance = offset.norm();
+
+        let (center, radius) = if distance + other.radius <= self.radius {
+            (self.center, self.radius)
+        } else if distance + self.radius <= other.radius {
+            (other.center, other.radius)
+        } else {
+            let radius = (distance + self.radius + other.radius) * 0.5;
+            let center = self.center + offset * ((radius - self.radius) / distance);
+            (center, radius)
+        };
+
+        *self = Self::from_center_and_radius(&center, radius, world);
+    }
+
+    /// 世界空间点是否在球内
+    pub fn intersects_point(&self, point: &Vector3) -> bool {
+        CoordinateSytem3::distance_squared(&self.center_world, point) <= self.radius_world * self.radius_world
+    }
+
+    /// 两个球世界空间是否相交
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let radius = self.radius_world + other.radius_world;
+        CoordinateSytem3::distance_squared(&self.center_world, &other.center_world) <= radius * radius
+    }
+
+    /// 世界空间球与视锥的关系
+    pub fn is_in_frustum(&self, frustum: &FrustumPlanes) -> EContainment {
+        frustum.intersects_sphere(&self.center_world, self.radius_world)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Matrix};
+
+    use super::BoundingSphere;
+
+    #[test]
+    fn test_bounding_sphere() {
+        let points = [Vector3::new(1., 0., 0.), Vector3::new(-1., 0., 0.), Vector3::new(0., 0.5, 0.)];
+        let sphere = BoundingSphere::from_points(&points, None);
+        assert_relative_eq!(sphere.center, Vector3::new(0., 0.25, 0.));
+        assert_relative_eq!(sphere.radius, (1.0 as Number + 0.0625).sqrt());
+
+        let mut merged = BoundingSphere::from_center_and_radius(&Vector3::new(0., 0., 0.), 1., None);
+        merged.merge(&BoundingSphere::from_center_and_radius(&Vector3::new(4., 0., 0.), 1., None), None);
+        assert_relative_eq!(merged.center, Vector3::new(2., 0., 0.));
+        assert_relative_eq!(merged.radius, 3.);
+
+        let mut world = Matrix::new_scaling(2.);
+        world.append_translation_mut(&Vector3::new(0., 5., 0.));
+        merged.update(&world);
+        assert_relative_eq!(merged.center_world, Vector3::new(4., 5., 0.));
+        assert_relative_eq!(merged.radius_world, 6.);
+    }
+}
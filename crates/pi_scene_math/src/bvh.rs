@@ -0,0 +1,473 @@
+use crate::{Number, Vector3, bounding_box::BoundingBox, ray::{Ray, IntersectionInfo}};
+
+/// SAH 分桶数量
+const BIN_COUNT: usize = 12;
+/// SAH 代价仍优于分裂时允许的最大叶子图元数
+const MAX_LEAF_SIZE: usize = 4;
+/// 遍历一个节点相对测试一个图元的代价
+const TRAVERSAL_COST: Number = 0.125;
+
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    minimum: Vector3,
+    maximum: Vector3,
+    /// 内部节点的左子节点下标, 右子节点为其后一个; 0 表示叶子 (根节点不会是子节点)
+    left: u32,
+    /// 叶子图元在排序数组中的起始位置与数量
+    start: u32,
+    count: u32,
+}
+
+/// 包围体层次 - 以轴对齐包围盒组织图元 (物体包围盒 三角形等)
+/// * 以分桶表面积启发式 (SAH) 构建
+/// * 图元移动但拓扑不变时可 `refit` 自底向上更新包围盒, 不重建
+/// * 查询结果写入 result 前先清空
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// 叶子中图元下标的排列
+    primitives: Vec<u32>,
+    /// 图元包围盒
+    bounds: Vec<(Vector3, Vector3)>,
+}
+
+impl Bvh {
+    /// 以图元包围盒 (minimum, maximum) 构建
+    pub fn build(bounds: &[(Vector3, Vector3)]) -> Self {
+        let mut bvh = Self {
+            nodes: vec![BvhNode { minimum: Vector3::repeat(Number::MAX), maximum: Vector3::repeat(-Number::MAX), left: 0, start: 0, count: 0 }],
+            primitives: (0..bounds.len() as u32).collect(),
+            bounds: bounds.to_vec(),
+        };
+        let centroids: Vec<Vector3> = bounds.iter().map(|(minimum, maximum)| (minimum + maximum) * 0.5).collect();
+
+        let mut stack = vec![(0usize, 0usize, bounds.len())];
+        while let Some((node, start, end)) = stack.pop() {
+            let (minimum, maximum) = bvh.union(start, end);
+            bvh.nodes[node] = BvhNode { minimum, maximum, left: 0, start: start as u32, count: (end - start) as u32 };
+
+            let Some(middle) = bvh.split(&centroids, start, end, surface_area(&minimum, &maximum)) else {
+                continue;
+            };
+            let left = bvh.nodes.len();
+            bvh.nodes.push(bvh.nodes[node]);
+            bvh.nodes.push(bvh.nodes[node]);
+            bvh.nodes[node].left = left as u32;
+            bvh.nodes[node].count = 0;
+            stack.push((left, start, middle));
+            stack.push((left + 1, middle, end));
+        }
+        bvh
+    }
+
+    /// 图元数量
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 根包围盒 (minimum, maximum)
+    pub fn root_bounds(&self) -> (Vector3, Vector3) {
+        (self.nodes[0].minimum, self.nodes[0].maximum)
+    }
+
+    /// 以新的图元包围盒自底向上更新, 数量与构建时不符时返回 false
+    pub fn refit(&mut self, bounds: &[(Vector3, Vector3)]) -> bool {
+        if bounds.len() != self.bounds.len() {
+            return false;
+        }
+        self.bounds.copy_from_slice(bounds);
+        // 子节点下标总大于父节点, 逆序即自底向上
+        for index in (0..self.nodes.len()).rev() {
+            let node = self.nodes[index];
+            let (minimum, maximum) = if node.left == 0 {
+                self.union(node.start as usize, (node.start + node.count) as usize)
+            } else {
+                let (left, right) = (&self.nodes[node.left as usize], &self.nodes[node.left as usize + 1]);
+                (left.minimum.inf(&right.minimum), left.maximum.sup(&right.maximum))
+            };
+            self.nodes[index].minimum = minimum;
+            self.nodes[index].maximum = maximum;
+        }
+        true
+    }
+
+    /// 射线求交 - 由近到远遍历, 返回 (图元下标, 距离)
+    /// * test 对包围盒与射线相交的图元做精确测试, 返回交点距离; 传入的射线长度已缩短到当前最近交点
+    /// * any_hit 为 true 时找到任一交点即返回, 用于遮挡测试
+    pub fn raycast(&self, ray: &Ray, any_hit: bool, mut test: impl FnMut(usize, &Ray) -> Option<Number>) -> Option<(usize, Number)> {
+        let mut ray = *ray;
+        let mut best: Option<(usize, Number)> = None;
+        let mut stack = Vec::with_capacity(64);
+        if ray.intersects_aabb(&self.nodes[0].minimum, &self.nodes[0].maximum).is_some() {
+            stack.push(0usize);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            // 入栈后最近交点可能已更新
+            if best.is_some() && ray.intersects_aabb(&node.minimum, &node.maximum).is_none() {
+                continue;
+            }
+            if node.left == 0 {
+                for primitive in &self.primitives[node.start as usize..(node.start + node.count) as usize] {
+                    if let Some(distance) = test(*primitive as usize, &ray) {
+                        if distance <= ray.length {
+                            best = Some((*primitive as usize, distance));
+                            ray.length = distance;
+                            if any_hit {
+                                return best;
+                            }
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let (left, right) = (node.left as usize, node.left as usize + 1);
+            let left_distance = ray.intersects_aabb(&self.nodes[left].minimum, &self.nodes[left].maximum);
+            let right_distance = ray.intersects_aabb(&self.nodes[right].minimum, &self.nodes[right].maximum);
+            match (left_distance, right_distance) {
+                (Some(a), Some(b)) => {
+                    // 近的后入栈先遍历
+                    if a <= b {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                },
+                (Some(_), None) => stack.push(left),
+                (None, Some(_)) => stack.push(right),
+                (None, None) => {},
+            }
+        }
+        best
+    }
+
+    /// 包围盒与射线相交的最近图元
+    pub fn raycast_bounds(&self, ray: &Ray) -> Option<(usize, Number)> {
+        self.raycast(ray, false, |primitive, ray| {
+            let (minimum, maximum) = &self.bounds[primitive];
+            ray.intersects_aabb(minimum, maximum)
+        })
+    }
+
+    /// 包围盒与轴对齐包围盒相交的图元
+    pub fn query_box(&self, minimum: &Vector3, maximum: &Vector3, result: &mut Vec<usize>) {
+        result.clear();
+        let intersects = |a: &Vector3, b: &Vector3| BoundingBox::intersects_min_max_min_max(minimum, maximum, a, b);
+        let mut stack = vec![0usize];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !intersects(&node.minimum, &node.maximum) {
+                continue;
+            }
+            if node.left == 0 {
+                let primitives = &self.primitives[node.start as usize..(node.start + node.count) as usize];
+                result.extend(primitives.iter().map(|primitive| *primitive as usize).filter(|primitive| {
+                    let (a, b) = &self.bounds[*primitive];
+                    intersects(a, b)
+                }));
+            } else {
+                stack.extend([node.left as usize, node.left as usize + 1]);
+            }
+        }
+    }
+
+    /// 排序数组 [start, end) 内图元包围盒的并集
+    fn union(&self, start: usize, end: usize) -> (Vector3, Vector3) {
+        self.primitives[start..end].iter().fold(
+            (Vector3::repeat(Number::MAX), Vector3::repeat(-Number::MAX)),
+            |(minimum, maximum), primitive| {
+                let (a, b) = &self.bounds[*primitive as usize];
+                (minimum.inf(a), maximum.sup(b))
+            },
+        )
+    }
+
+    /// 分桶 SAH 划分 [start, end), area 为其包围盒表面积; 返回划分位置, 作为叶子更优时返回 None
+    fn split(&mut self, centroids: &[Vector3], start: usize, end: usize, area: Number) -> Option<usize> {
+        let count = end - start;
+        if count <= 1 {
+            return None;
+        }
+
+        let (centroid_minimum, centroid_maximum) = self.primitives[start..end].iter().fold(
+            (Vector3::repeat(Number::MAX), Vector3::repeat(-Number::MAX)),
+            |(minimum, maximum), primitive| (minimum.inf(&centroids[*primitive as usize]), maximum.sup(&centroids[*primitive as usize])),
+        );
+        let extent = centroid_maximum - centroid_minimum;
+        let bin_of = |axis: usize, primitive: u32| -> usize {
+            let offset = (centroids[primitive as usize][axis] - centroid_minimum[axis]) / extent[axis];
+            ((offset * BIN_COUNT as Number) as usize).min(BIN_COUNT - 1)
+        };
+
+        // (代价, 轴, 分界桶)
+        let mut best: Option<(Number, usize, usize)> = None;
+        for axis in 0..3 {
+            if extent[axis] <= Number::EPSILON {
+                continue;
+            }
+            let mut bins = [(Vector3::repeat(Number::MAX), Vector3::repeat(-Number::MAX), 0usize); BIN_COUNT];
+            for primitive in &self.primitives[start..end] {
+                let bin = &mut bins[bin_of(axis, *primitive)];
+                let (a, b) = &self.bounds[*primitive as usize];
+                bin.0 = bin.0.inf(a);
+                bin.1 = bin.1.sup(b);
+                bin.2 += 1;
+            }
+
+            // 自右向左累积右侧面积与数量
+            let mut right_costs = [0.; BIN_COUNT];
+            let mut accumulated = (Vector3::repeat(Number::MAX), Vector3::repeat(-Number::MAX), 0usize);
+            for bin in (1..BIN_COUNT).rev() {
+                accumulated = (accumulated.0.inf(&bins[bin].0), accumulated.1.sup(&bins[bin].1), accumulated.2 + bins[bin].2);
+                right_costs[bin] = surface_area(&accumulated.0, &accumulated.1) * accumulated.2 as Number;
+            }
+            let mut accumulated = (Vector3::repeat(Number::MAX), Vector3::repeat(-Number::MAX), 0usize);
+            for bin in 0..BIN_COUNT - 1 {
+                accumulated = (accumulated.0.inf(&bins[bin].0), accumulated.1.sup(&bins[bin].1), accumulated.2 + bins[bin].2);
+                if accumulated.2 == 0 || accumulated.2 == count {
+                    continue;
+                }
+                let cost = surface_area(&accumulated.0, &accumulated.1) * accumulated.2 as Number + right_costs[bin + 1];
+                if best.map_or(true, |(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, bin));
+                }
+            }
+        }
+
+        match best {
+            Some((cost, axis, bin)) => {
+                let split_cost = TRAVERSAL_COST + if area > 0. { cost / area } else { count as Number };
+                if split_cost >= count as Number && count <= MAX_LEAF_SIZE {
+                    return None;
+                }
+                let mut middle = start;
+                for index in start..end {
+                    if bin_of(axis, self.primitives[index]) <= bin {
+                        self.primitives.swap(index, middle);
+                        middle += 1;
+                    }
+                }
+                Some(middle)
+            },
+            // 质心重合无法按空间划分, 图元过多时按数量对半
+            None if count > MAX_LEAF_SIZE => Some(start + count / 2),
+            None => None,
+        }
+    }
+}
+
+/// 轴对齐包围盒表面积
+fn surface_area(minimum: &Vector3, maximum: &Vector3) -> Number {
+    let size = (maximum - minimum).sup(&Vector3::zeros());
+    2. * (size.x * size.y + size.y * size.z + size.z * size.x)
+}
+
+/// 三角形射线命中结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    /// 三角形下标 (索引数组中第几个三角形)
+    pub triangle: usize,
+    pub intersection: IntersectionInfo,
+}
+
+/// 三角网格的包围体层次
+/// * 索引每 3 个为一个三角形
+/// * 顶点变形后以 `refit` 更新
+#[derive(Debug, Clone)]
+pub struct MeshBvh {
+    positions: Vec<Vector3>,
+    indices: Vec<[u32; 3]>,
+    bvh: Bvh,
+}
+
+impl MeshBvh {
+    /// 索引数量不为 3 的倍数或越界时返回 None
+    pub fn new(positions: &[Vector3], indices: &[u32]) -> Option<Self> {
+        if indices.len() % 3 != 0 || indices.iter().any(|index| *index as usize >= positions.len()) {
+            return None;
+        }
+        let indices: Vec<[u32; 3]> = indices.chunks_exact(3).map(|triangle| [triangle[0], triangle[1], triangle[2]]).collect();
+        let bounds = Self::triangle_bounds(positions, &indices);
+        Some(Self { positions: positions.to_vec(), indices, bvh: Bvh::build(&bounds) })
+    }
+
+    pub fn bvh(&self) -> &Bvh {
+        &self.bvh
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// 以变形后的顶点更新, 顶点数量不符时返回 false
+    pub fn refit(&mut self, positions: &[Vector3]) -> bool {
+        if positions.len() != self.positions.len() {
+            return false;
+        }
+        self.positions.copy_from_slice(positions);
+        let bounds = Self::triangle_bounds(&self.positions, &self.indices);
+        self.bvh.refit(&bounds)
+    }
+
+    /// 最近命中的三角形
+    pub fn raycast(&self, ray: &Ray) -> Option<TriangleHit> {
+        self.cast(ray, false)
+    }
+
+    /// 是否有任一三角形命中, 用于遮挡测试
+    pub fn any_hit(&self, ray: &Ray) -> bool {
+        self.cast(ray, true).is_some()
+    }
+
+    fn cast(&self, ray: &Ray, any_hit: bool) -> Option<TriangleHit> {
+        let mut nearest = None;
+        self.bvh.raycast(ray, any_hit, |triangle, ray| {
+            let [a, b, c] = self.indices[triangle];
+            let info = ray.intersects_triangle(&self.positions[a as usize], &self.positions[b as usize], &self.positions[c as usize])?;
+            nearest = Some(TriangleHit { triangle, intersection: info });
+            Some(info.distance)
+        })?;
+        nearest
+    }
+
+    fn triangle_bounds(positions: &[Vector3], indices: &[[u32; 3]]) -> Vec<(Vector3, Vector3)> {
+        indices.iter().map(|[a, b, c]| {
+            let (a, b, c) = (positions[*a as usize], positions[*b as usize], positions[*c as usize]);
+            (a.inf(&b).inf(&c), a.sup(&b).sup(&c))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, ray::Ray};
+
+    use super::{Bvh, MeshBvh};
+
+    /// 起伏的网格面, size x size 个格子
+    fn terrain(size: usize) -> (Vec<Vector3>, Vec<u32>) {
+        let mut positions = vec![];
+        for z in 0..=size {
+            for x in 0..=size {
+                let height = ((x as Number) * 0.7).sin() + ((z as Number) * 0.5).cos();
+                positions.push(Vector3::new(x as Number, height, z as Number));
+            }
+        }
+        let mut indices = vec![];
+        let row = (size + 1) as u32;
+        for z in 0..size as u32 {
+            for x in 0..size as u32 {
+                let i = z * row + x;
+                indices.extend_from_slice(&[i, i + row, i + 1, i + 1, i + row, i + row + 1]);
+            }
+        }
+        (positions, indices)
+    }
+
+    /// 线性同余伪随机数 [0, 1)
+    fn random(seed: &mut u32) -> Number {
+        *seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (*seed >> 8) as Number / (1 << 24) as Number
+    }
+
+    fn brute_force(positions: &[Vector3], indices: &[u32], ray: &Ray) -> Option<(usize, Number)> {
+        indices.chunks_exact(3).enumerate()
+            .filter_map(|(i, t)| ray.intersects_triangle(&positions[t[0] as usize], &positions[t[1] as usize], &positions[t[2] as usize]).map(|info| (i, info.distance)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    #[test]
+    fn test_mesh_bvh_raycast() {
+        let (positions, indices) = terrain(32);
+        assert!(MeshBvh::new(&positions, &indices[..4]).is_none());
+        assert!(MeshBvh::new(&positions, &[0, 1, 100000]).is_none());
+        let mesh = MeshBvh::new(&positions, &indices).unwrap();
+        assert_eq!(mesh.triangle_count(), 32 * 32 * 2);
+        assert!(mesh.bvh().node_count() > 1);
+
+        let mut seed = 7;
+        for _ in 0..200 {
+            let origin = Vector3::new(random(&mut seed) * 40. - 4., 5., random(&mut seed) * 40. - 4.);
+            let direction = Vector3::new(random(&mut seed) - 0.5, -1., random(&mut seed) - 0.5).normalize();
+            let ray = Ray::new(origin, direction, Number::MAX);
+            let expected = brute_force(&positions, &indices, &ray);
+            let hit = mesh.raycast(&ray);
+            assert_eq!(hit.is_some(), expected.is_some());
+            assert_eq!(mesh.any_hit(&ray), expected.is_some());
+            if let (Some(hit), Some((_, distance))) = (hit, expected) {
+                assert_relative_eq!(hit.intersection.distance, distance, epsilon = 0.0001);
+                // 重心坐标还原交点
+                let t = &indices[hit.triangle * 3..hit.triangle * 3 + 3];
+                let (bu, bv) = (hit.intersection.bu, hit.intersection.bv);
+                let point = positions[t[0] as usize] * (1. - bu - bv) + positions[t[1] as usize] * bu + positions[t[2] as usize] * bv;
+                assert_relative_eq!(point, origin + direction * distance, epsilon = 0.001);
+            }
+        }
+
+        // 射线长度不足
+        let ray = Ray::new(Vector3::new(10., 5., 10.), -Vector3::y(), 1.);
+        assert!(mesh.raycast(&ray).is_none());
+        assert!(!mesh.any_hit(&ray));
+    }
+
+    #[test]
+    fn test_mesh_bvh_refit() {
+        let (positions, indices) = terrain(8);
+        let mut mesh = MeshBvh::new(&positions, &indices).unwrap();
+        let ray = Ray::new(Vector3::new(4.2, 10., 4.3), -Vector3::y(), Number::MAX);
+        let before = mesh.raycast(&ray).unwrap();
+
+        let moved: Vec<Vector3> = positions.iter().map(|p| p + Vector3::new(0., 3., 0.)).collect();
+        assert!(mesh.refit(&moved));
+        assert!(!mesh.refit(&moved[1..]));
+        let after = mesh.raycast(&ray).unwrap();
+        assert_eq!(after.triangle, before.triangle);
+        assert_relative_eq!(after.intersection.distance, before.intersection.distance - 3., epsilon = 0.0001);
+        assert_relative_eq!(mesh.bvh().root_bounds().0.y, positions.iter().map(|p| p.y).fold(Number::MAX, Number::min) + 3., epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_bvh_bounds() {
+        let mut bounds = vec![];
+        for i in 0..100 {
+            let center = Vector3::new((i % 10) as Number * 3., 0., (i / 10) as Number * 3.);
+            bounds.push((center - Vector3::repeat(1.), center + Vector3::repeat(1.)));
+        }
+        // 重合的图元
+        for _ in 0..10 {
+            bounds.push((Vector3::repeat(100.), Vector3::repeat(101.)));
+        }
+        let bvh = Bvh::build(&bounds);
+        assert_eq!(bvh.len(), 110);
+        assert!(Bvh::build(&[]).raycast_bounds(&Ray::default()).is_none());
+
+        let ray = Ray::new(Vector3::new(-10., 0., 6.), Vector3::x(), Number::MAX);
+        let (primitive, distance) = bvh.raycast_bounds(&ray).unwrap();
+        assert_eq!(primitive, 20);
+        assert_relative_eq!(distance, 9.);
+
+        let (minimum, maximum) = (Vector3::new(2., -1., 2.), Vector3::new(7., 1., 4.));
+        let mut result = vec![];
+        bvh.query_box(&minimum, &maximum, &mut result);
+        result.sort();
+        let expected: Vec<usize> = bounds.iter().enumerate()
+            .filter(|(_, (a, b))| (0..3).all(|i| a[i] <= maximum[i] && b[i] >= minimum[i]))
+            .map(|(i, _)| i).collect();
+        assert_eq!(result, expected);
+
+        bvh.query_box(&Vector3::repeat(99.), &Vector3::repeat(102.), &mut result);
+        assert_eq!(result.len(), 10);
+    }
+}
@@ -1,6 +1,90 @@
 
-use crate::{Matrix, Number, coordiante_system::CoordinateSytem3, Perspective3, Orthographic3};
+use crate::{Matrix, Number, Vector3, Vector4, Isometry3, coordiante_system::{CoordinateSytem3, ECoordinateSytem3}, vector::{TToolMatrix, TToolVector3}, frustum::FrustumPlanes};
+pub use crate::coordiante_system::EDepthRange;
 
+/// 视口 - 屏幕像素坐标, 原点在左上角, y 向下
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: Number,
+    pub y: Number,
+    pub width: Number,
+    pub height: Number,
+}
+
+impl Viewport {
+    pub fn new(x: Number, y: Number, width: Number, height: Number) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// 点投影到屏幕 - 结果为 (像素 x, 像素 y, 窗口深度)
+    /// * `world` 点所在空间到世界空间的矩阵, 世界空间点传单位矩阵
+    /// * 窗口深度为 NDC 深度按 depth_range 映射到 [0, 1], 反向深度时近处为 1
+    /// * 点位于相机平面或其后方时返回 None
+    pub fn project(&self, point: &Vector3, world: &Matrix, view: &Matrix, projection: &Matrix, depth_range: EDepthRange) -> Option<Vector3> {
+        let transform = projection * view * world;
+        self.project_with_transform(point, &transform, depth_range)
+    }
+
+    /// 批量投影, result 与 points 一一对应
+    pub fn project_points(&self, points: &[Vector3], world: &Matrix, view: &Matrix, projection: &Matrix, depth_range: EDepthRange, result: &mut Vec<Option<Vector3>>) {
+        let transform = projection * view * world;
+        result.clear();
+        result.extend(points.iter().map(|point| self.project_with_transform(point, &transform, depth_range)));
+    }
+
+    /// 屏幕坐标反投影 - depth 为窗口深度 [0, 1], 矩阵不可逆时返回 None
+    #[allow(clippy::too_many_arguments)]
+    pub fn unproject(&self, x: Number, y: Number, depth: Number, world: &Matrix, view: &Matrix, projection: &Matrix, depth_range: EDepthRange) -> Option<Vector3> {
+        let mut inverse = projection * view * world;
+        if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+            return None;
+        }
+        let mut result = Vector3::zeros();
+        self.unproject_with_inverse(x, y, depth, &inverse, depth_range, &mut result);
+        Some(result)
+    }
+
+    /// 批量反投影, points 各元素为 (像素 x, 像素 y, 窗口深度), 矩阵不可逆时返回 false
+    pub fn unproject_points(&self, points: &[Vector3], world: &Matrix, view: &Matrix, projection: &Matrix, depth_range: EDepthRange, result: &mut Vec<Vector3>) -> bool {
+        result.clear();
+        let mut inverse = projection * view * world;
+        if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+            return false;
+        }
+        let mut unprojected = Vector3::zeros();
+        for point in points {
+            self.unproject_with_inverse(point.x, point.y, point.z, &inverse, depth_range, &mut unprojected);
+            result.push(unprojected);
+        }
+        true
+    }
+
+    /// 以 投影 * 视图 * 世界 矩阵投影
+    pub fn project_with_transform(&self, point: &Vector3, transform: &Matrix, depth_range: EDepthRange) -> Option<Vector3> {
+        let clip = transform * Vector4::new(point.x, point.y, point.z, 1.);
+        if clip.w <= Number::EPSILON {
+            return None;
+        }
+        let ndc_x = clip.x / clip.w;
+        let ndc_y = clip.y / clip.w;
+        let ndc_z = clip.z / clip.w;
+        Some(Vector3::new(
+            self.x + (ndc_x + 1.) * 0.5 * self.width,
+            self.y + (1. - ndc_y) * 0.5 * self.height,
+            depth_range.to_zero_to_one(ndc_z),
+        ))
+    }
+
+    /// 以 (投影 * 视图 * 世界) 的逆矩阵反投影
+    pub fn unproject_with_inverse(&self, x: Number, y: Number, depth: Number, inverse: &Matrix, depth_range: EDepthRange, result: &mut Vector3) {
+        let ndc_x = (x - self.x) / self.width * 2. - 1.;
+        let ndc_y = -((y - self.y) / self.height * 2. - 1.);
+        let ndc_z = depth_range.depth_from_zero_to_one(depth);
+        CoordinateSytem3::transform_coordinates_floats(ndc_x, ndc_y, ndc_z, inverse, result);
+    }
+}
+
+/// 正交投影, 输出深度范围由 depth_range 指定
 pub trait TOrthographicCameraTool {
     fn orthographic_rh(
         left: Number,
@@ -9,6 +93,7 @@ pub trait TOrthographicCameraTool {
         top: Number,
         znear: Number,
         zfar: Number,
+        depth_range: EDepthRange,
     ) -> Matrix;
     fn orthographic_lh(
         left: Number,
@@ -17,6 +102,7 @@ pub trait TOrthographicCameraTool {
         top: Number,
         znear: Number,
         zfar: Number,
+        depth_range: EDepthRange,
     ) -> Matrix;
 }
 
@@ -28,11 +114,12 @@ impl TOrthographicCameraTool for CoordinateSytem3 {
         top: Number,
         znear: Number,
         zfar: Number,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let mut result = Self::orthographic_lh(left, right, bottom, top, znear, zfar);
+        let mut result = Self::orthographic_lh(left, right, bottom, top, znear, zfar, depth_range);
         result[(2, 2)] = -result[(2, 2)];
 
-        return result;
+        result
     }
 
     fn orthographic_lh(
@@ -42,8 +129,8 @@ impl TOrthographicCameraTool for CoordinateSytem3 {
         top: Number,
         znear: Number,
         zfar: Number,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
         let n = znear;
         let f = zfar;
 
@@ -61,49 +148,92 @@ impl TOrthographicCameraTool for CoordinateSytem3 {
             i0,     i1,     d,      1.0,
         ]);
 
-        // println!(">>>>>>>>>> P: {:?}", result);
-        // println!(">>>>>>>>>> HP: {:?}", half_z_range * result);
-
-        return half_z_range * result;
-        
-        // Orthographic3::new(left, right, bottom, top, znear, zfar).as_matrix() * half_z_range
-        // if is_vertical_fixed {
-        //     Orthographic3::new(left, right, bottom, top, znear, zfar).as_matrix().clone()
-        // } else {
-        //     Perspective3::new(aspect, fov / aspect, znear, zfar).as_matrix().clone()
-        // }
+        depth_range.remap_negative_one_to_one(result)
     }
 }
 
+/// 透视投影
+/// * 输出深度范围由 depth_range 指定, 以下深度值均以 [0, 1] 描述
+/// * 标准: 近平面深度 0, 远平面深度 1
+/// * 反向深度 (reverse-Z): 近平面深度 1, 远平面深度 0, 远处精度更高, 深度测试需改为 Greater
+/// * 无限远反向深度: 远平面在无穷远, 深度 = znear / 视距
+/// * is_vertical_fixed 为 true 时 fov 为垂直视角, 否则为水平视角
 pub trait TPerspectiveCameraTool {
     fn perspective_for_reverse_rh(
         fov: Number,
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix;
     fn perspective_rh(
         fov: Number,
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix;
     fn perspective_for_reverse_lh(
         fov: Number,
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix;
     fn perspective_lh(
         fov: Number,
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix;
+    /// 远平面无穷远的标准深度透视
+    fn perspective_infinite_rh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix;
+    fn perspective_infinite_lh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix;
+    fn perspective_infinite_reverse_rh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix;
+    fn perspective_infinite_reverse_lh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix;
+    /// 标准透视深度值 转换为 视距
+    fn linearize_depth(depth: Number, znear: Number, zfar: Number, depth_range: EDepthRange) -> Number;
+    /// 反向深度值 转换为 视距
+    fn linearize_depth_reverse(depth: Number, znear: Number, zfar: Number, depth_range: EDepthRange) -> Number;
+    /// 无限远反向深度值 转换为 视距, 深度为 0 时为无穷远
+    fn linearize_depth_infinite_reverse(depth: Number, znear: Number, depth_range: EDepthRange) -> Number;
+}
+
+/// 透视投影 x y 方向缩放
+fn perspective_scale(fov: Number, aspect: Number, is_vertical_fixed: bool) -> (Number, Number) {
+    let t = 1.0 / (Number::tan(fov * 0.5));
+    let a = if is_vertical_fixed { t / aspect } else { t };
+    let b = if is_vertical_fixed { t } else { t * aspect };
+    (a, b)
 }
 
 impl TPerspectiveCameraTool for CoordinateSytem3 {
@@ -112,32 +242,24 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
         let n = znear;
         let f = zfar;
 
-        let t = 1.0 / (Number::tan(fov * 0.5));
-        let a = if is_vertical_fixed { t / aspect } else { t };
-        let b = if is_vertical_fixed { t } else { t * aspect };
-        let _c = (f + n) / (f - n);
-        let _d = -2.0 * f * n / (f - n);
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+        let c = n / (f - n);
+        let d = f * n / (f - n);
 
         let result = Matrix::from_column_slice(&[
             a, 0.0, 0.0, 0.0,
             0.0, b, 0.0, 0.0,
-            0.0, 0.0, -znear, -1.0,
-            0.0, 0.0, -1.0, 0.0,
+            0.0, 0.0, c, -1.0,
+            0.0, 0.0, d, 0.0,
         ]);
 
-        return half_z_range * result;
-
-        // if is_vertical_fixed {
-        //     Perspective3::new(aspect, fov, znear, zfar).as_matrix() * half_z_range
-        // } else {
-        //     Perspective3::new(aspect, fov / aspect, znear, zfar).as_matrix() * half_z_range
-        // }
+        depth_range.remap_zero_to_one(result)
     }
 
     fn perspective_rh(
@@ -145,15 +267,13 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
         let n = znear;
         let f = zfar;
 
-        let t = 1.0 / (Number::tan(fov * 0.5));
-        let a = if is_vertical_fixed { t / aspect } else { t };
-        let b = if is_vertical_fixed { t } else { t * aspect };
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
         let c = -(f + n) / (f - n);
         let d = -2.0 * f * n / (f - n);
         
@@ -164,14 +284,7 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
             0.0, 0.0, d, 0.0,
         ]);
 
-        return half_z_range * result;
-        
-
-        // if is_vertical_fixed {
-        //     Perspective3::new(aspect, fov, znear, zfar).as_matrix() * half_z_range
-        // } else {
-        //     Perspective3::new(aspect, fov / aspect, znear, zfar).as_matrix() * half_z_range
-        // }
+        depth_range.remap_negative_one_to_one(result)
     }
 
     fn perspective_for_reverse_lh(
@@ -179,32 +292,24 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
         let n = znear;
         let f = zfar;
 
-        let t = 1.0 / (Number::tan(fov * 0.5));
-        let a = if is_vertical_fixed { t / aspect } else { t };
-        let b = if is_vertical_fixed { t } else { t * aspect };
-        let _c = (f + n) / (f - n);
-        let _d = -2.0 * f * n / (f - n);
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+        let c = -n / (f - n);
+        let d = f * n / (f - n);
 
         let result = Matrix::from_column_slice(&[
             a, 0.0, 0.0, 0.0,
             0.0, b, 0.0, 0.0,
-            0.0, 0.0, -znear, 1.0,
-            0.0, 0.0, -1.0, 0.0,
+            0.0, 0.0, c, 1.0,
+            0.0, 0.0, d, 0.0,
         ]);
 
-        return half_z_range * result;
-
-        // if is_vertical_fixed {
-        //     Perspective3::new(aspect, fov, znear, zfar).as_matrix() * half_z_range
-        // } else {
-        //     Perspective3::new(aspect, fov / aspect, znear, zfar).as_matrix() * half_z_range
-        // }
+        depth_range.remap_zero_to_one(result)
     }
 
     fn perspective_lh(
@@ -212,15 +317,13 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
         aspect: Number,
         znear: Number,
         zfar: Number,
-        is_vertical_fixed: bool
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
     ) -> Matrix {
-        let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
         let n = znear;
         let f = zfar;
 
-        let t = 1.0 / (Number::tan(fov * 0.5));
-        let a = if is_vertical_fixed { t / aspect } else { t };
-        let b = if is_vertical_fixed { t } else { t * aspect };
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
         let c = (f + n) / (f - n);
         let d = -2.0 * f * n / (f - n);
 
@@ -231,12 +334,536 @@ impl TPerspectiveCameraTool for CoordinateSytem3 {
             0.0, 0.0, d, 0.0,
         ]);
 
-        return half_z_range * result;
-        
-        // if is_vertical_fixed {
-        //     Perspective3::new(aspect, fov, znear, zfar).as_matrix() * half_z_range
-        // } else {
-        //     Perspective3::new(aspect, fov / aspect, znear, zfar).as_matrix() * half_z_range
-        // }
+        depth_range.remap_negative_one_to_one(result)
+    }
+
+    fn perspective_infinite_rh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix {
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+
+        let result = Matrix::from_column_slice(&[
+            a, 0.0, 0.0, 0.0,
+            0.0, b, 0.0, 0.0,
+            0.0, 0.0, -1.0, -1.0,
+            0.0, 0.0, -2.0 * znear, 0.0,
+        ]);
+
+        depth_range.remap_negative_one_to_one(result)
+    }
+
+    fn perspective_infinite_lh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix {
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+
+        let result = Matrix::from_column_slice(&[
+            a, 0.0, 0.0, 0.0,
+            0.0, b, 0.0, 0.0,
+            0.0, 0.0, 1.0, 1.0,
+            0.0, 0.0, -2.0 * znear, 0.0,
+        ]);
+
+        depth_range.remap_negative_one_to_one(result)
+    }
+
+    fn perspective_infinite_reverse_rh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix {
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+
+        let result = Matrix::from_column_slice(&[
+            a, 0.0, 0.0, 0.0,
+            0.0, b, 0.0, 0.0,
+            0.0, 0.0, 0.0, -1.0,
+            0.0, 0.0, znear, 0.0,
+        ]);
+
+        depth_range.remap_zero_to_one(result)
+    }
+
+    fn perspective_infinite_reverse_lh(
+        fov: Number,
+        aspect: Number,
+        znear: Number,
+        is_vertical_fixed: bool,
+        depth_range: EDepthRange,
+    ) -> Matrix {
+        let (a, b) = perspective_scale(fov, aspect, is_vertical_fixed);
+
+        let result = Matrix::from_column_slice(&[
+            a, 0.0, 0.0, 0.0,
+            0.0, b, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+            0.0, 0.0, znear, 0.0,
+        ]);
+
+        depth_range.remap_zero_to_one(result)
+    }
+
+    fn linearize_depth(depth: Number, znear: Number, zfar: Number, depth_range: EDepthRange) -> Number {
+        let depth = depth_range.to_zero_to_one(depth);
+        znear * zfar / (zfar - depth * (zfar - znear))
     }
-}
\ No newline at end of file
+
+    fn linearize_depth_reverse(depth: Number, znear: Number, zfar: Number, depth_range: EDepthRange) -> Number {
+        let depth = depth_range.to_zero_to_one(depth);
+        znear * zfar / (znear + depth * (zfar - znear))
+    }
+
+    fn linearize_depth_infinite_reverse(depth: Number, znear: Number, depth_range: EDepthRange) -> Number {
+        let depth = depth_range.to_zero_to_one(depth);
+        znear / depth
+    }
+}
+
+/// 相机投影模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ECameraMode {
+    Perspective,
+    Orthographic,
+}
+
+/// 相机 - 视图 投影 视锥
+/// * 参数修改后标记脏, `calc_matrix` 时才重新计算矩阵与视锥
+/// * zfar 为无穷大时透视投影使用无限远版本
+/// * 投影按坐标系手性选择 lh / rh 版本
+#[derive(Debug, Clone)]
+pub struct Camera {
+    mode: ECameraMode,
+    coord: CoordinateSytem3,
+    depth_range: EDepthRange,
+    /// 透视投影是否反向深度
+    reverse_z: bool,
+    /// 透视视角
+    fov: Number,
+    /// fov 是否为垂直视角
+    is_vertical_fixed: bool,
+    /// 宽高比
+    aspect: Number,
+    znear: Number,
+    zfar: Number,
+    /// 正交投影范围 left right bottom top
+    orthographic: [Number; 4],
+    eye: Vector3,
+    target: Vector3,
+    up: Vector3,
+    view: Matrix,
+    projection: Matrix,
+    view_projection: Matrix,
+    view_inverse: Matrix,
+    projection_inverse: Matrix,
+    view_projection_inverse: Matrix,
+    frustum: FrustumPlanes,
+    view_dirty: bool,
+    projection_dirty: bool,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(CoordinateSytem3::default())
+    }
+}
+
+impl Camera {
+    /// 透视相机, 位于原点看向坐标系前方
+    pub fn new(coord: CoordinateSytem3) -> Self {
+        Self {
+            mode: ECameraMode::Perspective,
+            coord,
+            depth_range: EDepthRange::default(),
+            reverse_z: false,
+            fov: 0.8,
+            is_vertical_fixed: true,
+            aspect: 1.,
+            znear: 0.1,
+            zfar: 1000.,
+            orthographic: [-1., 1., -1., 1.],
+            eye: Vector3::zeros(),
+            target: coord.forward(),
+            up: coord.up(),
+            view: Matrix::identity(),
+            projection: Matrix::identity(),
+            view_projection: Matrix::identity(),
+            view_inverse: Matrix::identity(),
+            projection_inverse: Matrix::identity(),
+            view_projection_inverse: Matrix::identity(),
+            frustum: FrustumPlanes::default(),
+            view_dirty: true,
+            projection_dirty: true,
+        }
+    }
+
+    pub fn mode(&self) -> ECameraMode {
+        self.mode
+    }
+    pub fn set_mode(&mut self, mode: ECameraMode) {
+        self.projection_dirty = true;
+        self.mode = mode;
+    }
+
+    pub fn coordinate_system(&self) -> &CoordinateSytem3 {
+        &self.coord
+    }
+    pub fn set_coordinate_system(&mut self, coord: CoordinateSytem3) {
+        self.view_dirty = true;
+        self.projection_dirty = true;
+        self.coord = coord;
+    }
+
+    pub fn depth_range(&self) -> EDepthRange {
+        self.depth_range
+    }
+    pub fn set_depth_range(&mut self, depth_range: EDepthRange) {
+        self.projection_dirty = true;
+        self.depth_range = depth_range;
+    }
+
+    pub fn reverse_z(&self) -> bool {
+        self.reverse_z
+    }
+    pub fn set_reverse_z(&mut self, reverse_z: bool) {
+        self.projection_dirty = true;
+        self.reverse_z = reverse_z;
+    }
+
+    pub fn fov(&self) -> Number {
+        self.fov
+    }
+    pub fn set_fov(&mut self, fov: Number, is_vertical_fixed: bool) {
+        self.projection_dirty = true;
+        self.fov = fov;
+        self.is_vertical_fixed = is_vertical_fixed;
+    }
+    pub fn is_vertical_fixed(&self) -> bool {
+        self.is_vertical_fixed
+    }
+
+    pub fn aspect(&self) -> Number {
+        self.aspect
+    }
+    pub fn set_aspect(&mut self, aspect: Number) {
+        self.projection_dirty = true;
+        self.aspect = aspect;
+    }
+
+    pub fn znear(&self) -> Number {
+        self.znear
+    }
+    pub fn zfar(&self) -> Number {
+        self.zfar
+    }
+    pub fn set_near_far(&mut self, znear: Number, zfar: Number) {
+        self.projection_dirty = true;
+        self.znear = znear;
+        self.zfar = zfar;
+    }
+
+    /// 正交投影范围 left right bottom top
+    pub fn orthographic_bounds(&self) -> [Number; 4] {
+        self.orthographic
+    }
+    pub fn set_orthographic_bounds(&mut self, left: Number, right: Number, bottom: Number, top: Number) {
+        self.projection_dirty = true;
+        self.orthographic = [left, right, bottom, top];
+    }
+
+    pub fn eye(&self) -> Vector3 {
+        self.eye
+    }
+    pub fn target(&self) -> Vector3 {
+        self.target
+    }
+    pub fn up(&self) -> Vector3 {
+        self.up
+    }
+    pub fn lookat(&mut self, eye: &Vector3, target: &Vector3, up: &Vector3) {
+        self.view_dirty = true;
+        self.eye.copy_from(eye);
+        self.target.copy_from(target);
+        self.up.copy_from(up);
+    }
+
+    /// 视图或投影参数修改后尚未 `calc_matrix`
+    pub fn is_dirty(&self) -> bool {
+        self.view_dirty || self.projection_dirty
+    }
+
+    pub fn view_matrix(&self) -> &Matrix {
+        &self.view
+    }
+    pub fn projection_matrix(&self) -> &Matrix {
+        &self.projection
+    }
+    pub fn view_projection_matrix(&self) -> &Matrix {
+        &self.view_projection
+    }
+    pub fn view_inverse(&self) -> &Matrix {
+        &self.view_inverse
+    }
+    pub fn projection_inverse(&self) -> &Matrix {
+        &self.projection_inverse
+    }
+    pub fn view_projection_inverse(&self) -> &Matrix {
+        &self.view_projection_inverse
+    }
+    pub fn frustum(&self) -> &FrustumPlanes {
+        &self.frustum
+    }
+
+    /// 重新计算脏的矩阵与视锥
+    /// * 投影矩阵不可逆 (如正交投影 zfar 无穷大, znear 等于 zfar) 时返回 false, 矩阵与视锥保持上次结果
+    pub fn calc_matrix(&mut self) -> bool {
+        if !self.is_dirty() {
+            return true;
+        }
+
+        if self.projection_dirty {
+            let projection = self.calc_projection();
+            let mut inverse = projection;
+            if !projection.iter().all(|value| value.is_finite()) || !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+                return false;
+            }
+            self.projection = projection;
+            self.projection_inverse = inverse;
+        }
+
+        if self.view_dirty {
+            let mut view = Isometry3::identity();
+            self.coord.lookat(&self.eye, &self.target, &self.up, &mut view);
+            self.view = view.to_homogeneous();
+            self.view_inverse = view.inverse().to_homogeneous();
+        }
+
+        self.projection.mul_to(&self.view, &mut self.view_projection);
+        self.view_inverse.mul_to(&self.projection_inverse, &mut self.view_projection_inverse);
+        self.frustum.from_transform_matrix(&self.view_projection, self.depth_range);
+
+        self.view_dirty = false;
+        self.projection_dirty = false;
+        true
+    }
+
+    fn calc_projection(&self) -> Matrix {
+        let is_left = self.coord.mode() == ECoordinateSytem3::Left;
+        match self.mode {
+            ECameraMode::Perspective => {
+                let (fov, aspect, n, f, fixed, range) = (self.fov, self.aspect, self.znear, self.zfar, self.is_vertical_fixed, self.depth_range);
+                match (self.reverse_z, f.is_finite(), is_left) {
+                    (false, true, true) => CoordinateSytem3::perspective_lh(fov, aspect, n, f, fixed, range),
+                    (false, true, false) => CoordinateSytem3::perspective_rh(fov, aspect, n, f, fixed, range),
+                    (false, false, true) => CoordinateSytem3::perspective_infinite_lh(fov, aspect, n, fixed, range),
+                    (false, false, false) => CoordinateSytem3::perspective_infinite_rh(fov, aspect, n, fixed, range),
+                    (true, true, true) => CoordinateSytem3::perspective_for_reverse_lh(fov, aspect, n, f, fixed, range),
+                    (true, true, false) => CoordinateSytem3::perspective_for_reverse_rh(fov, aspect, n, f, fixed, range),
+                    (true, false, true) => CoordinateSytem3::perspective_infinite_reverse_lh(fov, aspect, n, fixed, range),
+                    (true, false, false) => CoordinateSytem3::perspective_infinite_reverse_rh(fov, aspect, n, fixed, range),
+                }
+            },
+            ECameraMode::Orthographic => {
+                let [left, right, bottom, top] = self.orthographic;
+                if is_left {
+                    CoordinateSytem3::orthographic_lh(left, right, bottom, top, self.znear, self.zfar, self.depth_range)
+                } else {
+                    CoordinateSytem3::orthographic_rh(left, right, bottom, top, self.znear, self.zfar, self.depth_range)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Matrix, coordiante_system::CoordinateSytem3, vector::TToolVector3};
+
+    use crate::frustum::EContainment;
+
+    use super::{TPerspectiveCameraTool, TOrthographicCameraTool, EDepthRange, Camera, ECameraMode, Viewport};
+
+    /// 视空间点投影后的 NDC 坐标
+    fn project(projection: &Matrix, point: &Vector3) -> Vector3 {
+        let mut result = Vector3::zeros();
+        CoordinateSytem3::transform_coordinates(point, projection, &mut result);
+        result
+    }
+
+    #[test]
+    fn test_perspective_depth() {
+        let (n, f): (Number, Number) = (0.5, 200.);
+        let fov = crate::consts::FRAC_PI_2;
+        for range in [EDepthRange::ZeroToOne, EDepthRange::NegativeOneToOne] {
+            let min = range.min();
+            for sign in [1., -1.] {
+                let (standard, reverse, infinite) = if sign > 0. {
+                    (
+                        CoordinateSytem3::perspective_lh(fov, 2., n, f, true, range),
+                        CoordinateSytem3::perspective_for_reverse_lh(fov, 2., n, f, true, range),
+                        CoordinateSytem3::perspective_infinite_reverse_lh(fov, 2., n, true, range),
+                    )
+                } else {
+                    (
+                        CoordinateSytem3::perspective_rh(fov, 2., n, f, true, range),
+                        CoordinateSytem3::perspective_for_reverse_rh(fov, 2., n, f, true, range),
+                        CoordinateSytem3::perspective_infinite_reverse_rh(fov, 2., n, true, range),
+                    )
+                };
+
+                let near = Vector3::new(0., 0., sign * n);
+                let far = Vector3::new(0., 0., sign * f);
+                assert_relative_eq!(project(&standard, &near).z, min, epsilon = 0.0001);
+                assert_relative_eq!(project(&standard, &far).z, 1., epsilon = 0.0001);
+                assert_relative_eq!(project(&reverse, &near).z, 1., epsilon = 0.0001);
+                assert_relative_eq!(project(&reverse, &far).z, min, epsilon = 0.0001);
+                assert_relative_eq!(project(&infinite, &near).z, 1., epsilon = 0.0001);
+                assert!(project(&infinite, &far).z > min);
+
+                // 深度线性化还原视距
+                let point = Vector3::new(1., -1., sign * 37.);
+                assert_relative_eq!(CoordinateSytem3::linearize_depth(project(&standard, &point).z, n, f, range), 37., max_relative = 0.001);
+                assert_relative_eq!(CoordinateSytem3::linearize_depth_reverse(project(&reverse, &point).z, n, f, range), 37., max_relative = 0.001);
+                assert_relative_eq!(CoordinateSytem3::linearize_depth_infinite_reverse(project(&infinite, &point).z, n, range), 37., max_relative = 0.001);
+
+                // 垂直视角 90 度, 宽高比 2
+                let corner = project(&reverse, &Vector3::new(2. * 10., 10., sign * 10.));
+                assert_relative_eq!(corner.x, 1., epsilon = 0.0001);
+                assert_relative_eq!(corner.y, 1., epsilon = 0.0001);
+            }
+        }
+
+        // 水平视角固定
+        let projection = CoordinateSytem3::perspective_infinite_reverse_lh(fov, 2., n, false, EDepthRange::ZeroToOne);
+        let corner = project(&projection, &Vector3::new(10., 5., 10.));
+        assert_relative_eq!(corner.x, 1., epsilon = 0.0001);
+        assert_relative_eq!(corner.y, 1., epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_orthographic_depth() {
+        for range in [EDepthRange::ZeroToOne, EDepthRange::NegativeOneToOne] {
+            let lh = CoordinateSytem3::orthographic_lh(-4., 4., -3., 3., 1., 11., range);
+            assert_relative_eq!(project(&lh, &Vector3::new(-4., 3., 1.)), Vector3::new(-1., 1., range.min()), epsilon = 0.0001);
+            assert_relative_eq!(project(&lh, &Vector3::new(4., -3., 11.)), Vector3::new(1., -1., 1.), epsilon = 0.0001);
+
+            let rh = CoordinateSytem3::orthographic_rh(-4., 4., -3., 3., 1., 11., range);
+            assert_relative_eq!(project(&rh, &Vector3::new(0., 0., -1.)).z, range.min(), epsilon = 0.0001);
+            assert_relative_eq!(project(&rh, &Vector3::new(0., 0., -11.)).z, 1., epsilon = 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_camera() {
+        let mut camera = Camera::new(CoordinateSytem3::right());
+        camera.set_aspect(2.);
+        camera.set_near_far(1., 100.);
+        camera.lookat(&Vector3::new(0., 0., 10.), &Vector3::zeros(), &Vector3::y());
+        assert!(camera.is_dirty());
+        camera.calc_matrix();
+        assert!(!camera.is_dirty());
+
+        let expect = CoordinateSytem3::perspective_rh(0.8, 2., 1., 100., true, EDepthRange::ZeroToOne);
+        assert_relative_eq!(*camera.projection_matrix(), expect, epsilon = 0.0001);
+        assert_relative_eq!(*camera.view_projection_matrix(), camera.projection_matrix() * camera.view_matrix(), epsilon = 0.0001);
+        assert_relative_eq!(camera.view_projection_matrix() * camera.view_projection_inverse(), Matrix::identity(), epsilon = 0.0001);
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., 0.)), EContainment::Inside);
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., 11.)), EContainment::Outside);
+
+        // 修改后未计算前保持缓存
+        camera.set_near_far(1., 5.);
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., 0.)), EContainment::Inside);
+        camera.calc_matrix();
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., 0.)), EContainment::Outside);
+
+        // 正交 + 反向深度不影响正交
+        camera.set_mode(ECameraMode::Orthographic);
+        camera.set_orthographic_bounds(-4., 4., -2., 2.);
+        camera.set_reverse_z(true);
+        camera.set_near_far(1., 100.);
+        camera.calc_matrix();
+        let mut ndc = Vector3::zeros();
+        CoordinateSytem3::transform_coordinates(&Vector3::new(4., 2., 9.), camera.view_projection_matrix(), &mut ndc);
+        assert_relative_eq!(ndc, Vector3::new(1., 1., 0.), epsilon = 0.0001);
+
+        // 无限远反向深度
+        camera.set_mode(ECameraMode::Perspective);
+        camera.set_near_far(1., Number::INFINITY);
+        camera.calc_matrix();
+        let expect = CoordinateSytem3::perspective_infinite_reverse_rh(0.8, 2., 1., true, EDepthRange::ZeroToOne);
+        assert_relative_eq!(*camera.projection_matrix(), expect, epsilon = 0.0001);
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., -10000.)), EContainment::Inside);
+
+        // 无限远标准深度
+        camera.set_reverse_z(false);
+        assert!(camera.calc_matrix());
+        let expect = CoordinateSytem3::perspective_infinite_rh(0.8, 2., 1., true, EDepthRange::ZeroToOne);
+        assert_relative_eq!(*camera.projection_matrix(), expect, epsilon = 0.0001);
+        assert_relative_eq!(camera.projection_matrix() * camera.projection_inverse(), Matrix::identity(), epsilon = 0.0001);
+        assert_relative_eq!(project(camera.projection_matrix(), &Vector3::new(0., 0., -1.)).z, 0., epsilon = 0.0001);
+        assert_relative_eq!(project(camera.projection_matrix(), &Vector3::new(0., 0., -1.0e6)).z, 1., epsilon = 0.0001);
+        assert_eq!(camera.frustum().contains_point(&Vector3::new(0., 0., -10000.)), EContainment::Inside);
+
+        // 投影不可逆时保持上次结果
+        camera.set_mode(ECameraMode::Orthographic);
+        assert!(!camera.calc_matrix());
+        assert!(camera.is_dirty());
+        assert_relative_eq!(*camera.projection_matrix(), expect, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_viewport_project() {
+        let viewport = Viewport::new(100., 50., 800., 600.);
+        let mut camera = Camera::new(CoordinateSytem3::left());
+        camera.set_aspect(800. / 600.);
+        camera.lookat(&Vector3::new(0., 0., -10.), &Vector3::zeros(), &Vector3::y());
+
+        let mut world = Matrix::identity();
+        world.append_translation_mut(&Vector3::new(0., 1., 0.));
+        let points = [Vector3::new(0., -1., 0.), Vector3::new(2., 1., 5.), Vector3::new(-3., 0.5, 40.)];
+
+        for range in [EDepthRange::ZeroToOne, EDepthRange::NegativeOneToOne] {
+            for reverse_z in [false, true] {
+                camera.set_depth_range(range);
+                camera.set_reverse_z(reverse_z);
+                camera.calc_matrix();
+                let (view, projection) = (*camera.view_matrix(), *camera.projection_matrix());
+
+                // 视线中心点位于视口中心
+                let center = viewport.project(&points[0], &world, &view, &projection, range).unwrap();
+                assert_relative_eq!(center.x, 500., epsilon = 0.001);
+                assert_relative_eq!(center.y, 350., epsilon = 0.001);
+                let near = viewport.project(&Vector3::new(0., -1., -9.9), &world, &view, &projection, range).unwrap();
+                assert_relative_eq!(near.z, if reverse_z { 1. } else { 0. }, epsilon = 0.0001);
+
+                // 往返
+                let mut screen = Vec::new();
+                viewport.project_points(&points, &world, &view, &projection, range, &mut screen);
+                let screen: Vec<Vector3> = screen.into_iter().map(|point| point.unwrap()).collect();
+                let mut back = Vec::new();
+                assert!(viewport.unproject_points(&screen, &world, &view, &projection, range, &mut back));
+                for (point, back) in points.iter().zip(back.iter()) {
+                    assert_relative_eq!(point, back, epsilon = 0.001);
+                }
+                let single = viewport.unproject(screen[1].x, screen[1].y, screen[1].z, &world, &view, &projection, range).unwrap();
+                assert_relative_eq!(single, points[1], epsilon = 0.001);
+
+                // 相机后方
+                assert!(viewport.project(&Vector3::new(0., 0., -20.), &world, &view, &projection, range).is_none());
+            }
+        }
+
+        assert!(viewport.unproject(0., 0., 0., &Matrix::zeros(), &Matrix::identity(), &Matrix::identity(), EDepthRange::ZeroToOne).is_none());
+    }
+}
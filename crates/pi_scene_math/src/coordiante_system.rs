@@ -1,15 +1,164 @@
-use nalgebra::{clamp, Matrix3, SimdComplexField, SimdBool, SimdPartialOrd};
-use crate::{vector::{TToolVector3, TToolMatrix, TToolRotation}, Vector3, Number, Matrix, Quaternion, Rotation3, Vector4, Point3, Isometry3};
+use nalgebra::{SimdComplexField, SimdBool, SimdPartialOrd};
+use crate::{vector::{TToolVector3, TToolMatrix, TToolRotation}, Vector3, Number, Matrix, Quaternion, Rotation3, Vector4, Point3, Isometry3, Matrix3};
 
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ECoordinateSytem3 {
     Left,
     Right,
 }
 
+/// NDC 深度范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EDepthRange {
+    /// [-1, 1] - OpenGL / WebGL
+    NegativeOneToOne,
+    /// [0, 1] - wgpu / D3D / Vulkan / Metal
+    #[default]
+    ZeroToOne,
+}
+
+impl EDepthRange {
+    /// 近平面 (标准深度) 的深度值
+    pub fn min(&self) -> Number {
+        match self {
+            EDepthRange::NegativeOneToOne => -1.,
+            EDepthRange::ZeroToOne => 0.,
+        }
+    }
+
+    /// 深度值转换到 [0, 1]
+    pub fn to_zero_to_one(&self, depth: Number) -> Number {
+        match self {
+            EDepthRange::NegativeOneToOne => (depth + 1.) * 0.5,
+            EDepthRange::ZeroToOne => depth,
+        }
+    }
+
+    /// [0, 1] 深度值转换到本范围
+    pub fn depth_from_zero_to_one(&self, depth: Number) -> Number {
+        match self {
+            EDepthRange::NegativeOneToOne => depth * 2. - 1.,
+            EDepthRange::ZeroToOne => depth,
+        }
+    }
+
+    /// 输出 [-1, 1] 深度的投影矩阵 转换为输出本范围
+    pub(crate) fn remap_negative_one_to_one(&self, projection: Matrix) -> Matrix {
+        match self {
+            EDepthRange::NegativeOneToOne => projection,
+            EDepthRange::ZeroToOne => {
+                let half_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.5, 0., 0., 0., 0.5, 1.].as_slice());
+                half_z_range * projection
+            },
+        }
+    }
+
+    /// 输出 [0, 1] 深度的投影矩阵 转换为输出本范围
+    pub(crate) fn remap_zero_to_one(&self, projection: Matrix) -> Matrix {
+        match self {
+            EDepthRange::NegativeOneToOne => {
+                let double_z_range: Matrix = Matrix::from_column_slice([1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 2., 0., 0., 0., -1., 1.].as_slice());
+                double_z_range * projection
+            },
+            EDepthRange::ZeroToOne => projection,
+        }
+    }
+}
+
+/// 欧拉角旋转顺序 - 绕固定 (父空间) 坐标轴依次旋转的顺序
+/// * 如 `XYZ` 先绕 X 再绕 Y 最后绕 Z, 即 M = Rz * Ry * Rx, 等价于按 Z Y X 顺序绕自身轴旋转
+/// * Blender 默认 `XYZ`, Unity 为 `ZXY`, yaw-pitch-roll (BabylonJS) 为 `ZXY`
+/// * 欧拉角向量各分量始终为绕 X Y Z 轴的角度, 与顺序无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EEulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+}
+
+impl EEulerOrder {
+    /// 依次旋转的三个轴序号
+    pub fn axes(&self) -> (usize, usize, usize) {
+        match self {
+            EEulerOrder::XYZ => (0, 1, 2),
+            EEulerOrder::XZY => (0, 2, 1),
+            EEulerOrder::YXZ => (1, 0, 2),
+            EEulerOrder::YZX => (1, 2, 0),
+            EEulerOrder::ZXY => (2, 0, 1),
+            EEulerOrder::ZYX => (2, 1, 0),
+        }
+    }
+    /// 轴序为 (0, 1, 2) 的偶置换时为 1, 否则为 -1
+    fn parity(&self) -> Number {
+        match self {
+            EEulerOrder::XYZ | EEulerOrder::YZX | EEulerOrder::ZXY => 1.,
+            EEulerOrder::XZY | EEulerOrder::YXZ | EEulerOrder::ZYX => -1.,
+        }
+    }
+}
+
+/// 绕第 axis 个坐标轴的旋转矩阵
+fn axis_rotation(axis: usize, angle: Number) -> Rotation3 {
+    match axis {
+        0 => Rotation3::from_axis_angle(&Vector3::x_axis(), angle),
+        1 => Rotation3::from_axis_angle(&Vector3::y_axis(), angle),
+        _ => Rotation3::from_axis_angle(&Vector3::z_axis(), angle),
+    }
+}
+
+/// 绕第 axis 个坐标轴的旋转四元数
+fn axis_quaternion(axis: usize, angle: Number) -> Quaternion {
+    match axis {
+        0 => Quaternion::from_axis_angle(&Vector3::x_axis(), angle),
+        1 => Quaternion::from_axis_angle(&Vector3::y_axis(), angle),
+        _ => Quaternion::from_axis_angle(&Vector3::z_axis(), angle),
+    }
+}
+
+/// 旋转矩阵分解为指定顺序的欧拉角
+/// * M = R_k * R_j * R_i, 中间轴角度取值 [-PI/2, PI/2]
+/// * 万向锁时 (中间轴 ±90°) 最后一轴角度取 0, 旋转全部归入第一轴
+fn euler_angles_from_matrix(m: &Matrix3, order: EEulerOrder, result: &mut Vector3) {
+    let (i, j, k) = order.axes();
+    let s = order.parity();
+
+    let cos_j = Number::sqrt(m[(i, i)] * m[(i, i)] + m[(j, i)] * m[(j, i)]);
+    let angle_j = Number::atan2(-s * m[(k, i)], cos_j);
+    let (angle_i, angle_k) = if cos_j > Number::EPSILON * 16. {
+        (
+            Number::atan2(s * m[(k, j)], m[(k, k)]),
+            Number::atan2(s * m[(j, i)], m[(i, i)]),
+        )
+    } else {
+        (Number::atan2(-s * m[(j, k)], m[(j, j)]), 0.)
+    };
+
+    result[i] = angle_i;
+    result[j] = angle_j;
+    result[k] = angle_k;
+}
+
+/// 坐标系向上的轴
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EUpAxis {
+    Y,
+    Z,
+}
+
+/// 坐标系约定 - 手性 + 向上的轴
+/// * 左手 Y 向上: X 右, Y 上, Z 前 (引擎默认, BabylonJS / Unity)
+/// * 右手 Y 向上: X 右, Y 上, Z 后 (glTF / OpenGL)
+/// * 右手 Z 向上: X 右, Y 前, Z 上 (Blender / 3ds Max)
+/// * 左手 Z 向上: X 右, Y 后, Z 上
+/// * Z 向上的约定均由对应手性的 Y 向上约定绕 X 轴旋转得到: (x, y, z) -> (x, -z, y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CoordinateSytem3 {
     mode: ECoordinateSytem3,
+    up: EUpAxis,
 }
 
 impl Default for CoordinateSytem3 {
@@ -19,41 +168,100 @@ impl Default for CoordinateSytem3 {
 }
 
 impl CoordinateSytem3 {
+    pub fn new(mode: ECoordinateSytem3, up: EUpAxis) -> Self {
+        Self { mode, up }
+    }
     pub fn left() -> Self {
-        Self { mode: ECoordinateSytem3::Left }
+        Self { mode: ECoordinateSytem3::Left, up: EUpAxis::Y }
     }
     pub fn right() -> Self {
-        Self { mode: ECoordinateSytem3::Right }
+        Self { mode: ECoordinateSytem3::Right, up: EUpAxis::Y }
     }
     pub fn mode(& self) -> ECoordinateSytem3 {
         self.mode 
     }
+    pub fn up_axis(&self) -> EUpAxis {
+        self.up
+    }
+
+    /// 本坐标系坐标到参考坐标系 (左手 Y 向上: 右 上 前) 的变换, 各列为本坐标系 X Y Z 轴在参考坐标系的方向
+    pub fn basis(&self) -> Matrix3 {
+        match (self.mode, self.up) {
+            (ECoordinateSytem3::Left, EUpAxis::Y) => Matrix3::identity(),
+            (ECoordinateSytem3::Right, EUpAxis::Y) => Matrix3::new(
+                1., 0., 0.,
+                0., 1., 0.,
+                0., 0., -1.,
+            ),
+            (ECoordinateSytem3::Right, EUpAxis::Z) => Matrix3::new(
+                1., 0., 0.,
+                0., 0., 1.,
+                0., 1., 0.,
+            ),
+            (ECoordinateSytem3::Left, EUpAxis::Z) => Matrix3::new(
+                1., 0., 0.,
+                0., 0., 1.,
+                0., -1., 0.,
+            ),
+        }
+    }
+
+    /// 本坐标系坐标转换到 target 坐标系坐标的变换 (正交, 手性不同时行列式为 -1)
+    pub fn conversion_to(&self, target: &CoordinateSytem3) -> Matrix3 {
+        target.basis().transpose() * self.basis()
+    }
+
+    /// 向量 (位置或方向) 转换到 target 坐标系
+    pub fn convert_vector(&self, target: &CoordinateSytem3, v0: &Vector3, result: &mut Vector3) {
+        result.copy_from(&(self.conversion_to(target) * v0));
+    }
+
+    /// 旋转转换到 target 坐标系 - 物理上为同一旋转, 手性改变时旋转角取反
+    pub fn convert_rotation(&self, target: &CoordinateSytem3, rotation: &Rotation3, result: &mut Rotation3) {
+        let conversion = self.conversion_to(target);
+        *result = Rotation3::from_matrix_unchecked(conversion * rotation.matrix() * conversion.transpose());
+    }
+
+    /// 四元数转换到 target 坐标系
+    pub fn convert_quaternion(&self, target: &CoordinateSytem3, quaternion: &Quaternion, result: &mut Quaternion) {
+        let mut rotation = Rotation3::identity();
+        self.convert_rotation(target, &quaternion.to_rotation_matrix(), &mut rotation);
+        *result = Quaternion::from_rotation_matrix(&rotation);
+    }
+
+    /// 变换矩阵转换到 target 坐标系 - 输入输出空间同时转换
+    pub fn convert_matrix(&self, target: &CoordinateSytem3, matrix: &Matrix, result: &mut Matrix) {
+        let conversion = self.conversion_to(target).to_homogeneous();
+        *result = conversion * matrix * conversion.transpose();
+    }
 }
 
 impl TToolVector3 for CoordinateSytem3 {
     #[inline(always)]
-    fn up() -> Vector3 {
-        Vector3::new(0., 1., 0.)
+    fn up(&self) -> Vector3 {
+        match self.up {
+            EUpAxis::Y => Vector3::new(0., 1., 0.),
+            EUpAxis::Z => Vector3::new(0., 0., 1.),
+        }
     }
 
     #[inline(always)]
-    fn down() -> Vector3 {
-        Vector3::new(0., -1., 0.)
+    fn down(&self) -> Vector3 {
+        -self.up()
     }
 
     #[inline(always)]
     fn backward(&self) -> Vector3 {
-        match self.mode {
-            ECoordinateSytem3::Left => Vector3::new(0., 0., -1.),
-            ECoordinateSytem3::Right => Vector3::new(0., 0., 1.),
-        }
+        -self.forward()
     }
 
     #[inline(always)]
     fn forward(&self) -> Vector3 {
-        match self.mode {
-            ECoordinateSytem3::Left => Vector3::new(0., 0., 1.),
-            ECoordinateSytem3::Right => Vector3::new(0., 0., -1.),
+        match (self.mode, self.up) {
+            (ECoordinateSytem3::Left, EUpAxis::Y) => Vector3::new(0., 0., 1.),
+            (ECoordinateSytem3::Right, EUpAxis::Y) => Vector3::new(0., 0., -1.),
+            (ECoordinateSytem3::Left, EUpAxis::Z) => Vector3::new(0., -1., 0.),
+            (ECoordinateSytem3::Right, EUpAxis::Z) => Vector3::new(0., 1., 0.),
         }
     }
 
@@ -110,10 +318,10 @@ impl TToolVector3 for CoordinateSytem3 {
 
     #[inline(always)]
     fn clamp(v0: &Vector3, min: &Vector3, max: &Vector3, result: &mut Vector3) {
-        // result.x = self.x.max(min.x).min(max.x);
-        // result.y = self.y.max(min.y).min(max.y);
-        // result.z = self.z.max(min.z).min(max.z);
-        result.copy_from(clamp(v0, min, max));
+        // nalgebra::clamp 对向量按偏序比较, 分量不可比时原样返回, 此处需逐分量限定
+        result.x = v0.x.max(min.x).min(max.x);
+        result.y = v0.y.max(min.y).min(max.y);
+        result.z = v0.z.max(min.z).min(max.z);
     }
 
     #[inline(always)]
@@ -183,16 +391,21 @@ impl TToolVector3 for CoordinateSytem3 {
         result.z = x * m[2] + y * m[6] + z * m[10];
     }
 
-    fn rotation_from_axis(_axis1: &Vector3, _axis2: &Vector3, _axis3: &Vector3, _result: &mut Vector3) {
-        // todo!()
+    #[inline(always)]
+    fn rotation_from_axis(axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, order: EEulerOrder, result: &mut Vector3) {
+        let rotation = Self::rotation_matrix_from_axises(axis1, axis2, axis3);
+        euler_angles_from_matrix(rotation.matrix(), order, result);
     }
 
-    fn rotate_by_quaternion(_v0: &Vector3, _quaternion: &Quaternion, _result: &mut Vector3) {
-        // todo!()
+    #[inline(always)]
+    fn rotate_by_quaternion(v0: &Vector3, quaternion: &Quaternion, result: &mut Vector3) {
+        result.copy_from(&quaternion.transform_vector(v0));
     }
 
-    fn rotate_by_quaternion_around_point(_v0: &Vector3, _quaternion: &Quaternion, _point: Vector3, _result: &mut Vector3) {
-        // todo!()
+    #[inline(always)]
+    fn rotate_by_quaternion_around_point(v0: &Vector3, quaternion: &Quaternion, point: Vector3, result: &mut Vector3) {
+        let offset = v0 - point;
+        result.copy_from(&(quaternion.transform_vector(&offset) + point));
     }
 }
 
@@ -265,7 +478,7 @@ impl TToolMatrix for CoordinateSytem3 {
         m[13] = cofact_13 * det_inv;
         m[14] = cofact_23 * det_inv;
         m[15] = cofact_33 * det_inv;
-        return true;
+        true
     }
     #[inline(always)]
     fn mul_to(a: &Matrix, b: &Matrix, y: & mut Matrix) {
@@ -313,8 +526,8 @@ impl TToolMatrix for CoordinateSytem3 {
     }
 
     #[inline(always)]
-    fn matrix4_compose_euler_angle(scaling: &Vector3, eulers: &Vector3, translation: &Vector3, result: &mut Matrix) {
-        let rotation = Rotation3::from_euler_angles(eulers.z, eulers.x, eulers.y);
+    fn matrix4_compose_euler_angle(scaling: &Vector3, eulers: &Vector3, order: EEulerOrder, translation: &Vector3, result: &mut Matrix) {
+        let rotation = Self::rotation_matrix_from_euler_angles(eulers.x, eulers.y, eulers.z, order);
         Self::matrix4_compose_rotation(scaling, &rotation, translation, result);
     }
 
@@ -353,7 +566,7 @@ impl TToolMatrix for CoordinateSytem3 {
                 // *rotation = Self::rotation_matrix_from_euler_angles(0., 0., 0.);
             }
 
-            return true;
+            true
         }
         else {
             let det = m.determinant();
@@ -368,9 +581,10 @@ impl TToolMatrix for CoordinateSytem3 {
             let m21 = m[(2, 1)];
             let m22 = m[(2, 2)];
 
-            let sx = Number::sqrt(m00 * m00 + m01 * m01 + m02 * m02);
-            let mut sy = Number::sqrt(m10 * m10 + m11 * m11 + m12 * m12);
-            let sz = Number::sqrt(m20 * m20 + m21 * m21 + m22 * m22);
+            // 缩放为各列 (基向量) 的长度
+            let sx = Number::sqrt(m00 * m00 + m10 * m10 + m20 * m20);
+            let mut sy = Number::sqrt(m01 * m01 + m11 * m11 + m21 * m21);
+            let sz = Number::sqrt(m02 * m02 + m12 * m12 + m22 * m22);
 
             if det < 0. {
                 sy *= -1.;
@@ -380,20 +594,20 @@ impl TToolMatrix for CoordinateSytem3 {
                 scaling.x = sx; scaling.y = sy; scaling.z = sz;
             }
 
-            if Number::EPSILON.eq(&sx.abs()) || Number::EPSILON.eq(&sy.abs()) || Number::EPSILON.eq(&sz.abs()) {
+            if sx.abs() < Number::EPSILON || sy.abs() < Number::EPSILON || sz.abs() < Number::EPSILON {
                 if let Some(rotation) =  rotation {
                     rotation.clone_from(&Rotation3::from_euler_angles(0., 0., 0.));
                 }
-                return false;
+                false
             } else {
                 if let Some(rotation) =  rotation {
                     rotation.matrix_mut_unchecked().copy_from_slice(&[
-                        m00 / sx, m10 / sy, m20 / sz,
-                        m01 / sx, m11 / sy, m21 / sz,
-                        m02 / sx, m12 / sy, m22 / sz,
+                        m00 / sx, m10 / sx, m20 / sx,
+                        m01 / sy, m11 / sy, m21 / sy,
+                        m02 / sz, m12 / sz, m22 / sz,
                     ]);
                 }
-                return true;
+                true
             }
         }
     }
@@ -560,23 +774,25 @@ impl TToolRotation for CoordinateSytem3 {
         ]);
     }
     #[inline(always)]
-    fn quaternion_from_euler_angles(x: Number, y: Number, z: Number) -> Quaternion {
-        Quaternion::from_rotation_matrix(&Self::rotation_matrix_from_euler_angles(x, y, z))
+    fn quaternion_from_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder) -> Quaternion {
+        let angles = [x, y, z];
+        let (i, j, k) = order.axes();
+        axis_quaternion(k, angles[k]) * axis_quaternion(j, angles[j]) * axis_quaternion(i, angles[i])
     }
 
     #[inline(always)]
     fn quaternion_mut_yaw_pitch_roll(&self, yaw: Number, pitch: Number, roll: Number, result: &mut Quaternion) {
-        *result = Quaternion::from_rotation_matrix(&Self::rotation_matrix_from_euler_angles(yaw, pitch, roll));
+        *result = Self::quaternion_from_euler_angles(pitch, yaw, roll, EEulerOrder::ZXY);
     }
 
     #[inline(always)]
     fn quaternion_from_unit_vector(axis: &nalgebra::Unit<Vector3>, vec_to: &Vector3) -> Quaternion {
         let r = Vector3::dot(axis, vec_to) + 1.0;
-        let quat = if r < f32::EPSILON {
-            if f32::abs(axis.x) > f32::abs(axis.z) {
-                nalgebra::Quaternion::new(0., -1.0 * axis.y, axis.x, 0.)
+        let quat = if r < Number::EPSILON {
+            if Number::abs(axis.x) > Number::abs(axis.z) {
+                nalgebra::Quaternion::new(0., -axis.y, axis.x, 0.)
             } else {
-                nalgebra::Quaternion::new(0., 0.0, -1.0 * axis.z, axis.y)
+                nalgebra::Quaternion::new(0., 0.0, -axis.z, axis.y)
             }
         } else {
             let temp = Vector3::cross(axis, vec_to);
@@ -585,136 +801,356 @@ impl TToolRotation for CoordinateSytem3 {
         Quaternion::from_quaternion(quat)
     }
 
-    fn quaternion_mut_axis(&self, _axis1: &Vector3, _axis2: &Vector3, _axis3: &Vector3, _result: &mut Quaternion) {
-        todo!()
+    #[inline(always)]
+    fn quaternion_mut_axis(&self, axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, result: &mut Quaternion) {
+        *result = Quaternion::from_rotation_matrix(&Self::rotation_matrix_from_axises(axis1, axis2, axis3));
     }
 
     #[inline(always)]
-    fn quaternion_to_euler_angles(&self, quaternion: &Quaternion, result: &mut Vector3) {
-        let (z, x, y) = quaternion.euler_angles();
-        // match self.mode {
-        //     ECoordinateSytem3::Left => {
-        //         result.copy_from_slice(&[-x, -y, -z]);
-        //     },
-        //     ECoordinateSytem3::Right => {
-        //         result.copy_from_slice(&[x, y, z]);
-        //     },
-        // }
-        result.copy_from_slice(&[x, y, z]);
+    fn quaternion_to_euler_angles(&self, quaternion: &Quaternion, order: EEulerOrder, result: &mut Vector3) {
+        euler_angles_from_matrix(quaternion.to_rotation_matrix().matrix(), order, result);
     }
 
     #[inline(always)]
-    fn rotation_matrix_from_euler_angles_toref(x: Number, y: Number, z: Number, result: &mut Rotation3) {
-        let (sr, cr) = x.simd_sin_cos();
-        let (sp, cp) = y.simd_sin_cos();
-        let (sy, cy) = z.simd_sin_cos();
-
-        result.matrix_mut_unchecked().copy_from_slice(&[
-            cy * cp,
-            cy * sp * sr - sy * cr,
-            cy * sp * cr + sy * sr,
-            sy * cp,
-            sy * sp * sr + cy * cr,
-            sy * sp* cr - cy * sr,
-            -sp,
-            cp * sr,
-            cp * cr,
-        ]);
+    fn rotation_matrix_from_euler_angles_toref(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Rotation3) {
+        let angles = [x, y, z];
+        let (i, j, k) = order.axes();
+        *result = axis_rotation(k, angles[k]) * axis_rotation(j, angles[j]) * axis_rotation(i, angles[i]);
     }
     #[inline(always)]
-    fn rotation_matrix_from_euler_angles(x: Number, y: Number, z: Number) -> Rotation3 {
-        // match self.mode {
-        //     ECoordinateSytem3::Left => {
-        //         Rotation3::from_euler_angles(-x, -y, -z)
-        //     },
-        //     ECoordinateSytem3::Right => {
-        //         Rotation3::from_euler_angles(x, y, z)
-        //     },
-        // }
-
-        // Rotation3::from_euler_angles(x, y, z)
-        
-        let (sr, cr) = x.simd_sin_cos();
-        let (sp, cp) = y.simd_sin_cos();
-        let (sy, cy) = z.simd_sin_cos();
-
-        Rotation3::from_matrix_unchecked(Matrix3::from_column_slice(
-            &[
-                cy * cp,
-                cy * sp * sr - sy * cr,
-                cy * sp * cr + sy * sr,
-                sy * cp,
-                sy * sp * sr + cy * cr,
-                sy * sp* cr - cy * sr,
-                -sp,
-                cp * sr,
-                cp * cr,
-            ]
-        ))
+    fn rotation_matrix_from_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder) -> Rotation3 {
+        let mut result = Rotation3::identity();
+        Self::rotation_matrix_from_euler_angles_toref(x, y, z, order, &mut result);
+        result
     }
 
     #[inline(always)]
     fn rotation_matrix_mut_yaw_pitch_roll(&self, yaw: Number, pitch: Number, roll: Number, result: &mut Rotation3) {
-        // match self.mode {
-        //     ECoordinateSytem3::Left => {
-        //         result.clone_from(&Rotation3::from_euler_angles(-roll, -pitch, -yaw));
-        //     },
-        //     ECoordinateSytem3::Right => {
-        //         result.clone_from(&Rotation3::from_euler_angles(roll, pitch, yaw));
-        //     },
-        // }
-        *result = Rotation3::from_euler_angles(roll, pitch, yaw);
+        Self::rotation_matrix_from_euler_angles_toref(pitch, yaw, roll, EEulerOrder::ZXY, result);
     }
 
-    fn rotation_matrix_mut_axis(&self, _axis1: &Vector3, _axis2: &Vector3, _axis3: &Vector3, _result: &mut Rotation3) {
-        todo!()
+    #[inline(always)]
+    fn rotation_matrix_mut_axis(&self, axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, result: &mut Rotation3) {
+        *result = Self::rotation_matrix_from_axises(axis1, axis2, axis3);
     }
 
     #[inline(always)]
-    fn rotation_matrix_to_euler_angles(&self, rotation: &Rotation3, result: &mut Vector3) {
-        let (z, x, y) = rotation.euler_angles();
-        // match self.mode {
-        //     ECoordinateSytem3::Left => {
-        //         result.copy_from_slice(&[-x, -y, -z]);
-        //     },
-        //     ECoordinateSytem3::Right => {
-        //         result.copy_from_slice(&[x, y, z]);
-        //     },
-        // }
-        result.copy_from_slice(&[x, y, z]);
+    fn rotation_matrix_to_euler_angles(&self, rotation: &Rotation3, order: EEulerOrder, result: &mut Vector3) {
+        euler_angles_from_matrix(rotation.matrix(), order, result);
     }
 
     #[inline(always)]
-    fn quaternion_mut_euler_angles(x: Number, y: Number, z: Number, result: &mut Quaternion) {
-        *result = Self::quaternion_from_euler_angles(x, y, z);
+    fn quaternion_mut_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Quaternion) {
+        *result = Self::quaternion_from_euler_angles(x, y, z, order);
     }
 
     #[inline(always)]
-    fn rotation_matrix_mut_euler_angles(x: Number, y: Number, z: Number, result: &mut Rotation3) {
-        *result = Self::rotation_matrix_from_euler_angles(x, y, z);
+    fn rotation_matrix_mut_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Rotation3) {
+        Self::rotation_matrix_from_euler_angles_toref(x, y, z, order, result);
     }
-    ///
-    /// 会卡死原因未知
+    /// 三个轴作为旋转矩阵的三列, 即局部 X Y Z 轴旋转后的方向
+    /// * 左右手坐标系下数值一致, axis3 应与 axis1 x axis2 同向
+    /// * 原先的 Rotation3::from_matrix 迭代次数不限, 输入有误差或为镜像基时无法收敛而卡死;
+    ///   此处改为正交化后直接构建: axis1 保持方向, Z 轴取 axis1 x axis2 与 axis3 的平均,
+    ///   axis2 与 axis1 平行时仍可由 axis3 确定
+    /// * axis3 与 axis1 x axis2 反向为镜像基, 无法由旋转表示, 此时忽略 axis3
     #[inline(always)]
     fn rotation_matrix_from_axises(axis1: &Vector3, axis2: &Vector3, axis3: &Vector3) -> Rotation3 {
-        let mut m = Matrix3::identity();
-        m.copy_from_slice(&[
-            axis1.x, axis1.y, axis1.z,
-            axis2.x, axis2.y, axis2.z,
-            axis3.x, axis3.y, axis3.z,
-        ]);
-        return Rotation3::from_matrix(&m);
+        let x = axis1.normalize();
+        let z1 = x.cross(axis2).try_normalize(Number::EPSILON).unwrap_or_else(Vector3::zeros);
+        let z3 = axis3.try_normalize(Number::EPSILON).unwrap_or_else(Vector3::zeros);
+        let z = if z1.dot(&z3) < 0. { z1 } else { z1 + z3 };
+        let z = (z - x * x.dot(&z)).normalize();
+        let y = z.cross(&x);
+        Rotation3::from_matrix_unchecked(Matrix3::from_columns(&[x, y, z]))
     }
     #[inline(always)]
     fn quaternion_from_axis_angle(axis1: &Vector3, radian: Number) -> Quaternion {
-        let (sin, cos) = f32::simd_sin_cos(radian / 2.);
+        let (sin, cos) = Number::simd_sin_cos(radian / 2.);
         let quat = nalgebra::Quaternion::new(
             cos,
             axis1.x * sin,
             axis1.y * sin,
             axis1.z * sin,
         );
-        return Quaternion::from_quaternion(quat);
+        Quaternion::from_quaternion(quat)
+    }
+
+    fn quaternion_slerp(from: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion) {
+        let mut dot = from.coords.dot(&to.coords);
+        let flip = dot < 0.;
+        if flip {
+            dot = -dot;
+        }
+
+        let (scale_from, mut scale_to) = if dot > 0.999999 {
+            (1. - amount, amount)
+        } else {
+            let angle = dot.acos();
+            let inverse_sin = 1. / angle.sin();
+            (((1. - amount) * angle).sin() * inverse_sin, (amount * angle).sin() * inverse_sin)
+        };
+        if flip {
+            scale_to = -scale_to;
+        }
+
+        *result = Quaternion::new_normalize(nalgebra::Quaternion::from(from.coords * scale_from + to.coords * scale_to));
+    }
+
+    fn quaternion_nlerp(from: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion) {
+        let scale_to = if from.coords.dot(&to.coords) < 0. { -amount } else { amount };
+        *result = Quaternion::new_normalize(nalgebra::Quaternion::from(from.coords * (1. - amount) + to.coords * scale_to));
+    }
+
+    fn quaternion_squad(from: &Quaternion, control1: &Quaternion, control2: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion) {
+        let mut outer = Quaternion::identity();
+        let mut inner = Quaternion::identity();
+        Self::quaternion_slerp(from, to, amount, &mut outer);
+        Self::quaternion_slerp(control1, control2, amount, &mut inner);
+        Self::quaternion_slerp(&outer, &inner, 2. * amount * (1. - amount), result);
+    }
+
+    fn quaternion_squad_control(prev: &Quaternion, current: &Quaternion, next: &Quaternion, result: &mut Quaternion) {
+        // 相邻帧取与当前帧同一半球的表示
+        let current_raw = current.quaternion();
+        let prev = if prev.coords.dot(&current.coords) < 0. { -prev.quaternion() } else { *prev.quaternion() };
+        let next = if next.coords.dot(&current.coords) < 0. { -next.quaternion() } else { *next.quaternion() };
+
+        let inverse = current.inverse();
+        let log_prev = (inverse.quaternion() * prev).ln();
+        let log_next = (inverse.quaternion() * next).ln();
+        *result = Quaternion::new_normalize(current_raw * ((log_prev + log_next) * -0.25).exp());
+    }
+
+    fn quaternion_hermite(value1: &Quaternion, tangent1: &Vector4, value2: &Quaternion, tangent2: &Vector4, amount: Number, frame_delta: Number, result: &mut Quaternion) {
+        let squared = amount * amount;
+        let cubed = amount * squared;
+        let part1 = ((2. * cubed) - (3. * squared)) + 1.;
+        let part2 = (-2. * cubed) + (3. * squared);
+        let part3 = (cubed - (2. * squared)) + amount;
+        let part4 = cubed - squared;
+
+        let coords = value1.coords * part1
+            + value2.coords * part2
+            + tangent1 * (part3 * frame_delta)
+            + tangent2 * (part4 * frame_delta);
+        *result = Quaternion::new_normalize(nalgebra::Quaternion::from(coords));
+    }
+}
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Vector4, Matrix, Quaternion, Rotation3, vector::{TToolVector3, TToolMatrix, TToolRotation}};
+
+    use super::{CoordinateSytem3, ECoordinateSytem3, EEulerOrder, EUpAxis};
+
+    #[test]
+    fn test_matrix4_decompose_rotation() {
+        let expect_scaling = Vector3::new(1., 2., 3.);
+        let expect_rotation = Rotation3::from_euler_angles(0.3, -1.1, 0.7);
+        let expect_translation = Vector3::new(4., -5., 6.);
+        let mut matrix = Matrix::identity();
+        CoordinateSytem3::matrix4_compose_rotation(&expect_scaling, &expect_rotation, &expect_translation, &mut matrix);
+
+        let mut scaling = Vector3::zeros();
+        let mut rotation = Rotation3::identity();
+        let mut translation = Vector3::zeros();
+        assert!(CoordinateSytem3::matrix4_decompose_rotation(&matrix, Some(&mut scaling), Some(&mut rotation), Some(&mut translation)));
+        assert_relative_eq!(scaling, expect_scaling, epsilon = 0.0001);
+        assert_relative_eq!(rotation, expect_rotation, epsilon = 0.0001);
+        assert_relative_eq!(translation, expect_translation, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut result = Vector3::zeros();
+        // 分量有大有小, 按偏序不可比
+        CoordinateSytem3::clamp(&Vector3::new(-2., 0.5, 3.), &Vector3::zeros(), &Vector3::new(1., 1., 1.), &mut result);
+        assert_relative_eq!(result, Vector3::new(0., 0.5, 1.));
     }
 
-}
\ No newline at end of file
+    fn quaternion() -> Quaternion {
+        Quaternion::from_axis_angle(&nalgebra::Unit::new_normalize(Vector3::new(1., -2., 0.5)), 2.3)
+    }
+
+    #[test]
+    fn test_rotation_from_axises() {
+        let expect = quaternion();
+        let axis1 = expect * Vector3::x();
+        let axis2 = expect * Vector3::y();
+        let axis3 = expect * Vector3::z();
+
+        // 三轴为旋转矩阵的列, 左右手坐标系下数值一致, 均与 nalgebra 结果比较
+        for coord in [CoordinateSytem3::left(), CoordinateSytem3::right()] {
+            let mut quaternion = Quaternion::identity();
+            coord.quaternion_mut_axis(&axis1, &axis2, &axis3, &mut quaternion);
+            assert_relative_eq!(quaternion.angle_to(&expect), 0., epsilon = 0.001);
+
+            let mut rotation = Rotation3::identity();
+            coord.rotation_matrix_mut_axis(&axis1, &axis2, &axis3, &mut rotation);
+            assert_relative_eq!(rotation, expect.to_rotation_matrix(), epsilon = 0.0001);
+            assert_relative_eq!(rotation, Rotation3::from_basis_unchecked(&[axis1, axis2, axis3]), epsilon = 0.0001);
+        }
+
+        // 带误差的输入不再卡死, 结果为正交矩阵
+        let rotation = CoordinateSytem3::rotation_matrix_from_axises(&(axis1 * 1.01), &(axis2 + axis1 * 0.01), &axis3);
+        assert_relative_eq!(rotation.matrix() * rotation.matrix().transpose(), nalgebra::Matrix3::identity(), epsilon = 0.0001);
+        assert_relative_eq!(rotation * Vector3::x(), axis1, epsilon = 0.0001);
+
+        // axis2 与 axis1 平行时由 axis3 确定
+        let rotation = CoordinateSytem3::rotation_matrix_from_axises(&axis1, &axis1, &axis3);
+        assert_relative_eq!(rotation, expect.to_rotation_matrix(), epsilon = 0.0001);
+
+        // axis3 有误差时参与平均, 结果与 axis1 x axis2 不同
+        let rotation = CoordinateSytem3::rotation_matrix_from_axises(&axis1, &axis2, &(axis3 + axis2 * 0.1));
+        assert!((rotation * Vector3::z() - axis3).norm() > 0.01);
+        assert_relative_eq!(rotation * Vector3::x(), axis1, epsilon = 0.0001);
+
+        // 镜像基忽略 axis3
+        let rotation = CoordinateSytem3::rotation_matrix_from_axises(&axis1, &axis2, &-axis3);
+        assert_relative_eq!(rotation, expect.to_rotation_matrix(), epsilon = 0.0001);
+
+        let mut eulers = Vector3::zeros();
+        CoordinateSytem3::rotation_from_axis(&axis1, &axis2, &axis3, EEulerOrder::YXZ, &mut eulers);
+        let rotation = CoordinateSytem3::rotation_matrix_from_euler_angles(eulers.x, eulers.y, eulers.z, EEulerOrder::YXZ);
+        assert_relative_eq!(rotation, expect.to_rotation_matrix(), epsilon = 0.0001);
+    }
+
+    const ORDERS: [EEulerOrder; 6] = [EEulerOrder::XYZ, EEulerOrder::XZY, EEulerOrder::YXZ, EEulerOrder::YZX, EEulerOrder::ZXY, EEulerOrder::ZYX];
+
+    #[test]
+    fn test_euler_order() {
+        let (x, y, z) = (0.3, -0.7, 1.2);
+        let rx = Rotation3::from_axis_angle(&Vector3::x_axis(), x);
+        let ry = Rotation3::from_axis_angle(&Vector3::y_axis(), y);
+        let rz = Rotation3::from_axis_angle(&Vector3::z_axis(), z);
+
+        assert_relative_eq!(CoordinateSytem3::rotation_matrix_from_euler_angles(x, y, z, EEulerOrder::XYZ), Rotation3::from_euler_angles(x, y, z), epsilon = 0.0001);
+        assert_relative_eq!(CoordinateSytem3::rotation_matrix_from_euler_angles(x, y, z, EEulerOrder::ZXY), ry * rx * rz, epsilon = 0.0001);
+        assert_relative_eq!(CoordinateSytem3::rotation_matrix_from_euler_angles(x, y, z, EEulerOrder::ZYX), rx * ry * rz, epsilon = 0.0001);
+
+        let coord = CoordinateSytem3::default();
+        let mut yaw_pitch_roll = Rotation3::identity();
+        coord.rotation_matrix_mut_yaw_pitch_roll(y, x, z, &mut yaw_pitch_roll);
+        assert_relative_eq!(yaw_pitch_roll, ry * rx * rz, epsilon = 0.0001);
+
+        for order in ORDERS {
+            let rotation = CoordinateSytem3::rotation_matrix_from_euler_angles(x, y, z, order);
+            let quaternion = CoordinateSytem3::quaternion_from_euler_angles(x, y, z, order);
+            assert_relative_eq!(quaternion.to_rotation_matrix(), rotation, epsilon = 0.0001);
+
+            let mut eulers = Vector3::zeros();
+            coord.rotation_matrix_to_euler_angles(&rotation, order, &mut eulers);
+            assert_relative_eq!(eulers, Vector3::new(x, y, z), epsilon = 0.0001);
+
+            coord.quaternion_to_euler_angles(&quaternion, order, &mut eulers);
+            assert_relative_eq!(eulers, Vector3::new(x, y, z), epsilon = 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_euler_gimbal_lock() {
+        let coord = CoordinateSytem3::default();
+        let half_pi = crate::consts::FRAC_PI_2;
+        for order in ORDERS {
+            let (_, j, _) = order.axes();
+            for middle in [half_pi, -half_pi, half_pi - 0.0001, -half_pi + 0.0002] {
+                let mut angles = Vector3::new(0.4, -1.1, 0.8);
+                angles[j] = middle;
+
+                let rotation = CoordinateSytem3::rotation_matrix_from_euler_angles(angles.x, angles.y, angles.z, order);
+                let mut eulers = Vector3::zeros();
+                coord.rotation_matrix_to_euler_angles(&rotation, order, &mut eulers);
+                assert!(eulers.iter().all(|v| v.is_finite()));
+
+                let restored = CoordinateSytem3::rotation_matrix_from_euler_angles(eulers.x, eulers.y, eulers.z, order);
+                assert_relative_eq!(restored, rotation, epsilon = 0.001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_by_quaternion() {
+        let quaternion = quaternion();
+        let v0 = Vector3::new(3., 1., -2.);
+
+        let mut result = Vector3::zeros();
+        CoordinateSytem3::rotate_by_quaternion(&v0, &quaternion, &mut result);
+        assert_relative_eq!(result, quaternion.to_rotation_matrix() * v0, epsilon = 0.0001);
+
+        let point = Vector3::new(1., 1., 1.);
+        CoordinateSytem3::rotate_by_quaternion_around_point(&v0, &quaternion, point, &mut result);
+        let expect = nalgebra::Isometry3::rotation_wrt_point(quaternion, point.into()).transform_point(&v0.into());
+        assert_relative_eq!(result, expect.coords, epsilon = 0.0001);
+
+        // 绕轴上的点旋转, 点保持不动
+        CoordinateSytem3::rotate_by_quaternion_around_point(&point, &quaternion, point, &mut result);
+        assert_relative_eq!(result, point, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_convert_coordinate() {
+        let blender = CoordinateSytem3::new(ECoordinateSytem3::Right, EUpAxis::Z);
+        let gltf = CoordinateSytem3::right();
+        let engine = CoordinateSytem3::left();
+
+        let v = Vector3::new(1., 2., 3.);
+        let mut result = Vector3::zeros();
+        blender.convert_vector(&engine, &v, &mut result);
+        assert_relative_eq!(result, Vector3::new(1., 3., 2.));
+        blender.convert_vector(&gltf, &v, &mut result);
+        assert_relative_eq!(result, Vector3::new(1., 3., -2.));
+        blender.convert_vector(&engine, &blender.up(), &mut result);
+        assert_relative_eq!(result, engine.up());
+        gltf.convert_vector(&engine, &gltf.forward(), &mut result);
+        assert_relative_eq!(result, engine.forward());
+
+        // 旋转后的向量转换 == 转换后的旋转作用于转换后的向量
+        let quaternion = quaternion();
+        let mut converted = Quaternion::identity();
+        blender.convert_quaternion(&engine, &quaternion, &mut converted);
+        let mut expect = Vector3::zeros();
+        blender.convert_vector(&engine, &(quaternion * v), &mut expect);
+        blender.convert_vector(&engine, &v, &mut result);
+        assert_relative_eq!(converted * result, expect, epsilon = 0.0001);
+
+        // 往返转换不变
+        let mut back = Quaternion::identity();
+        engine.convert_quaternion(&blender, &converted, &mut back);
+        assert_relative_eq!(back, quaternion, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_quaternion_interpolation() {
+        let about_z = |angle: Number| Quaternion::from_axis_angle(&Vector3::z_axis(), angle);
+        let mut result = Quaternion::identity();
+
+        CoordinateSytem3::quaternion_slerp(&about_z(0.), &about_z(1.2), 0.25, &mut result);
+        assert_relative_eq!(result, about_z(0.3), epsilon = 0.0001);
+        // 同一旋转的负表示, 仍走最短路径
+        let negative = Quaternion::new_unchecked(-about_z(1.2).into_inner());
+        CoordinateSytem3::quaternion_slerp(&about_z(0.), &negative, 0.25, &mut result);
+        assert_relative_eq!(result.angle(), 0.3, epsilon = 0.0001);
+        CoordinateSytem3::quaternion_nlerp(&about_z(0.), &negative, 1., &mut result);
+        assert_relative_eq!(result.angle(), 1.2, epsilon = 0.0001);
+        CoordinateSytem3::quaternion_nlerp(&about_z(0.2), &about_z(0.4), 0.5, &mut result);
+        assert_relative_eq!(result, about_z(0.3), epsilon = 0.0001);
+
+        // 匀速旋转序列, squad 与 slerp 一致
+        let keys = [about_z(0.), about_z(0.5), about_z(1.), about_z(1.5)];
+        let mut control1 = Quaternion::identity();
+        let mut control2 = Quaternion::identity();
+        CoordinateSytem3::quaternion_squad_control(&keys[0], &keys[1], &keys[2], &mut control1);
+        CoordinateSytem3::quaternion_squad_control(&keys[1], &keys[2], &keys[3], &mut control2);
+        CoordinateSytem3::quaternion_squad(&keys[1], &control1, &control2, &keys[2], 0.3, &mut result);
+        assert_relative_eq!(result, about_z(0.65), epsilon = 0.0001);
+
+        // Hermite - 切线为匀速旋转的导数
+        let tangent = |angle: Number| Vector4::new(0., 0., (angle * 0.5).cos() * 0.5, -(angle * 0.5).sin() * 0.5);
+        CoordinateSytem3::quaternion_hermite(&about_z(0.), &tangent(0.), &about_z(1.), &tangent(1.), 0.5, 1., &mut result);
+        assert_relative_eq!(result, about_z(0.5), epsilon = 0.001);
+        CoordinateSytem3::quaternion_hermite(&about_z(0.), &tangent(0.), &about_z(1.), &tangent(1.), 1., 1., &mut result);
+        assert_relative_eq!(result, about_z(1.), epsilon = 0.0001);
+    }
+}
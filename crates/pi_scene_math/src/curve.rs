@@ -0,0 +1,394 @@
+use crate::{Number, Vector2, Vector3, Vector4, Quaternion, coordiante_system::CoordinateSytem3, vector::TToolRotation, easing::Easing};
+
+/// 曲线数值类型 - 时间 插值比例 等
+pub type KeyFrameCurveValue = Number;
+
+/// 可插值的曲线值
+pub trait TInterpolateVector {
+    fn interpolate(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self;
+    /// self + rhs * amount
+    fn append(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self;
+    /// 三次 Hermite 插值, 切线按 frame_delta 缩放
+    fn hermite(value1: &Self, tangent1: &Self, value2: &Self, tangent2: &Self, amount: KeyFrameCurveValue, frame_delta: KeyFrameCurveValue) -> Self;
+    /// 分量数量
+    fn size() -> usize;
+    fn scale(&self, rhs: KeyFrameCurveValue) -> Self;
+}
+
+/// Hermite 基函数
+fn hermite_parts(amount: KeyFrameCurveValue) -> (KeyFrameCurveValue, KeyFrameCurveValue, KeyFrameCurveValue, KeyFrameCurveValue) {
+    let squared = amount * amount;
+    let cubed = amount * squared;
+    let part1 = ((2. * cubed) - (3. * squared)) + 1.;
+    let part2 = (-2. * cubed) + (3. * squared);
+    let part3 = (cubed - (2. * squared)) + amount;
+    let part4 = cubed - squared;
+    (part1, part2, part3, part4)
+}
+
+macro_rules! impl_interpolate_vector {
+    ($ty: ty, $size: expr) => {
+        impl TInterpolateVector for $ty {
+            fn interpolate(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self {
+                self.scale(1.0 - amount) + rhs.scale(amount)
+            }
+            fn hermite(value1: &Self, tangent1: &Self, value2: &Self, tangent2: &Self, amount: KeyFrameCurveValue, frame_delta: KeyFrameCurveValue) -> Self {
+                let (part1, part2, part3, part4) = hermite_parts(amount);
+                value1.scale(part1) + value2.scale(part2) + tangent1.scale(part3 * frame_delta) + tangent2.scale(part4 * frame_delta)
+            }
+            fn append(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self {
+                *self + rhs.scale(amount)
+            }
+            fn size() -> usize {
+                $size
+            }
+            fn scale(&self, rhs: KeyFrameCurveValue) -> Self {
+                self * rhs
+            }
+        }
+    };
+}
+
+impl_interpolate_vector!(Number, 1);
+impl_interpolate_vector!(Vector2, 2);
+impl_interpolate_vector!(Vector3, 3);
+impl_interpolate_vector!(Vector4, 4);
+
+/// 旋转曲线值 - 未归一化四元数, 以便同时表示切线
+/// * interpolate 为最短路径球面插值, hermite 结果归一化 (glTF CUBICSPLINE)
+/// * append scale 按分量计算
+impl TInterpolateVector for nalgebra::Quaternion<Number> {
+    fn interpolate(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self {
+        let mut result = Quaternion::identity();
+        CoordinateSytem3::quaternion_slerp(&Quaternion::new_normalize(*self), &Quaternion::new_normalize(*rhs), amount, &mut result);
+        result.into_inner()
+    }
+    fn hermite(value1: &Self, tangent1: &Self, value2: &Self, tangent2: &Self, amount: KeyFrameCurveValue, frame_delta: KeyFrameCurveValue) -> Self {
+        let mut result = Quaternion::identity();
+        CoordinateSytem3::quaternion_hermite(&Quaternion::new_normalize(*value1), &tangent1.coords, &Quaternion::new_normalize(*value2), &tangent2.coords, amount, frame_delta, &mut result);
+        result.into_inner()
+    }
+    fn append(&self, rhs: &Self, amount: KeyFrameCurveValue) -> Self {
+        self + rhs * amount
+    }
+    fn size() -> usize {
+        4
+    }
+    fn scale(&self, rhs: KeyFrameCurveValue) -> Self {
+        self * rhs
+    }
+}
+
+/// 关键帧到下一关键帧的插值方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EKeyFrameInterpolation {
+    /// 保持当前值直到下一关键帧
+    Step,
+    Linear,
+    /// 使用当前关键帧出切线与下一关键帧入切线
+    Hermite,
+    /// 以三次贝塞尔缓动 (x1, y1, x2, y2) 调整插值比例, 同 CSS cubic-bezier
+    CubicBezier(KeyFrameCurveValue, KeyFrameCurveValue, KeyFrameCurveValue, KeyFrameCurveValue),
+    /// 以缓动函数调整插值比例
+    Eased(Easing),
+}
+
+/// 曲线在关键帧范围外的取值方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ECurveInfinity {
+    /// 保持首尾值
+    #[default]
+    Constant,
+    /// 循环
+    Loop,
+    /// 往返
+    PingPong,
+    /// 循环, 每个周期叠加首尾值差
+    RelativeRepeat,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyFrame<T> {
+    pub time: KeyFrameCurveValue,
+    pub value: T,
+    /// 入切线 (单位时间变化量)
+    pub in_tangent: T,
+    /// 出切线 (单位时间变化量)
+    pub out_tangent: T,
+    pub interpolation: EKeyFrameInterpolation,
+}
+
+impl<T: TInterpolateVector + Clone> KeyFrame<T> {
+    pub fn new(time: KeyFrameCurveValue, value: T, interpolation: EKeyFrameInterpolation) -> Self {
+        let zero = value.scale(0.);
+        Self { time, value, in_tangent: zero.clone(), out_tangent: zero, interpolation }
+    }
+    pub fn step(time: KeyFrameCurveValue, value: T) -> Self {
+        Self::new(time, value, EKeyFrameInterpolation::Step)
+    }
+    pub fn linear(time: KeyFrameCurveValue, value: T) -> Self {
+        Self::new(time, value, EKeyFrameInterpolation::Linear)
+    }
+    pub fn hermite(time: KeyFrameCurveValue, value: T, in_tangent: T, out_tangent: T) -> Self {
+        Self { time, value, in_tangent, out_tangent, interpolation: EKeyFrameInterpolation::Hermite }
+    }
+    pub fn cubic_bezier(time: KeyFrameCurveValue, value: T, x1: KeyFrameCurveValue, y1: KeyFrameCurveValue, x2: KeyFrameCurveValue, y2: KeyFrameCurveValue) -> Self {
+        Self::new(time, value, EKeyFrameInterpolation::CubicBezier(x1, y1, x2, y2))
+    }
+    pub fn eased(time: KeyFrameCurveValue, value: T, easing: Easing) -> Self {
+        Self::new(time, value, EKeyFrameInterpolation::Eased(easing))
+    }
+}
+
+/// 关键帧曲线
+/// * 关键帧按时间升序保存
+/// * `sample_with_cursor` 使用外部游标缓存上次所在区间, 顺序播放时为 O(1); 游标由调用方持有, 曲线可共享
+#[derive(Debug, Clone)]
+pub struct FrameCurve<T> {
+    keys: Vec<KeyFrame<T>>,
+    pub pre_infinity: ECurveInfinity,
+    pub post_infinity: ECurveInfinity,
+}
+
+impl<T> Default for FrameCurve<T> {
+    fn default() -> Self {
+        Self { keys: Vec::new(), pre_infinity: ECurveInfinity::Constant, post_infinity: ECurveInfinity::Constant }
+    }
+}
+
+impl<T: TInterpolateVector + Clone> FrameCurve<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按时间插入关键帧, 时间相同时替换
+    pub fn add_key(&mut self, key: KeyFrame<T>) {
+        match self.keys.binary_search_by(|other| other.time.total_cmp(&key.time)) {
+            Ok(index) => self.keys[index] = key,
+            Err(index) => self.keys.insert(index, key),
+        }
+    }
+
+    pub fn keys(&self) -> &[KeyFrame<T>] {
+        &self.keys
+    }
+
+    pub fn start(&self) -> KeyFrameCurveValue {
+        self.keys.first().map(|key| key.time).unwrap_or(0.)
+    }
+
+    pub fn end(&self) -> KeyFrameCurveValue {
+        self.keys.last().map(|key| key.time).unwrap_or(0.)
+    }
+
+    pub fn duration(&self) -> KeyFrameCurveValue {
+        self.end() - self.start()
+    }
+
+    /// 采样, 无关键帧时返回 None
+    pub fn sample(&self, time: KeyFrameCurveValue) -> Option<T> {
+        let mut cursor = 0;
+        self.sample_with_cursor(time, &mut cursor)
+    }
+
+    /// 以游标缓存区间采样
+    pub fn sample_with_cursor(&self, time: KeyFrameCurveValue, cursor: &mut usize) -> Option<T> {
+        let first = self.keys.first()?;
+        let last = self.keys.last()?;
+        let duration = last.time - first.time;
+        if duration <= 0. {
+            return Some(first.value.clone());
+        }
+
+        let infinity = if time < first.time {
+            self.pre_infinity
+        } else if time > last.time {
+            self.post_infinity
+        } else {
+            ECurveInfinity::Constant
+        };
+
+        let cycle = ((time - first.time) / duration).floor();
+        let local = match infinity {
+            ECurveInfinity::Constant => time.clamp(first.time, last.time),
+            ECurveInfinity::Loop | ECurveInfinity::RelativeRepeat => time - cycle * duration,
+            ECurveInfinity::PingPong => {
+                let local = time - cycle * duration;
+                if (cycle as i64).rem_euclid(2) == 1 {
+                    first.time + last.time - local
+                } else {
+                    local
+                }
+            },
+        };
+
+        let value = self.sample_local(local, cursor);
+        if infinity == ECurveInfinity::RelativeRepeat {
+            let delta = last.value.append(&first.value, -1.);
+            Some(value.append(&delta, cycle))
+        } else {
+            Some(value)
+        }
+    }
+
+    /// 采样关键帧范围内的时间
+    fn sample_local(&self, time: KeyFrameCurveValue, cursor: &mut usize) -> T {
+        let count = self.keys.len();
+        if time >= self.keys[count - 1].time {
+            *cursor = count - 1;
+            return self.keys[count - 1].value.clone();
+        }
+
+        // 游标所在或下一区间命中时不再搜索
+        let in_segment = |index: usize| index + 1 < count && self.keys[index].time <= time && time < self.keys[index + 1].time;
+        if !in_segment(*cursor) {
+            if in_segment(*cursor + 1) {
+                *cursor += 1;
+            } else {
+                *cursor = self.keys.partition_point(|key| key.time <= time).saturating_sub(1);
+            }
+        }
+
+        let key1 = &self.keys[*cursor];
+        let key2 = &self.keys[*cursor + 1];
+        let frame_delta = key2.time - key1.time;
+        let amount = (time - key1.time) / frame_delta;
+        match key1.interpolation {
+            EKeyFrameInterpolation::Step => key1.value.clone(),
+            EKeyFrameInterpolation::Linear => key1.value.interpolate(&key2.value, amount),
+            EKeyFrameInterpolation::Hermite => T::hermite(&key1.value, &key1.out_tangent, &key2.value, &key2.in_tangent, amount, frame_delta),
+            EKeyFrameInterpolation::CubicBezier(x1, y1, x2, y2) => key1.value.interpolate(&key2.value, cubic_bezier_ease(x1, y1, x2, y2, amount)),
+            EKeyFrameInterpolation::Eased(easing) => easing.interpolate(&key1.value, &key2.value, amount),
+        }
+    }
+}
+
+/// 三次贝塞尔缓动 - 控制点 (0, 0) (x1, y1) (x2, y2) (1, 1), 求 x 对应的 y
+pub fn cubic_bezier_ease(x1: KeyFrameCurveValue, y1: KeyFrameCurveValue, x2: KeyFrameCurveValue, y2: KeyFrameCurveValue, x: KeyFrameCurveValue) -> KeyFrameCurveValue {
+    let bezier = |p1: KeyFrameCurveValue, p2: KeyFrameCurveValue, t: KeyFrameCurveValue| {
+        let u = 1. - t;
+        3. * u * u * t * p1 + 3. * u * t * t * p2 + t * t * t
+    };
+    let derivative = |p1: KeyFrameCurveValue, p2: KeyFrameCurveValue, t: KeyFrameCurveValue| {
+        let u = 1. - t;
+        3. * u * u * p1 + 6. * u * t * (p2 - p1) + 3. * t * t * (1. - p2)
+    };
+
+    let x = x.clamp(0., 1.);
+    // 牛顿迭代, 斜率过小时退回二分
+    let mut t = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, t) - x;
+        if error.abs() < 1e-6 {
+            return bezier(y1, y2, t);
+        }
+        let slope = derivative(x1, x2, t);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        t = (t - error / slope).clamp(0., 1.);
+    }
+
+    let (mut low, mut high) = (0., 1.);
+    t = x;
+    for _ in 0..32 {
+        let value = bezier(x1, x2, t);
+        if (value - x).abs() < 1e-6 {
+            break;
+        }
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) * 0.5;
+    }
+    bezier(y1, y2, t)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector2, Vector3, Vector4, Quaternion};
+
+    use super::{TInterpolateVector, FrameCurve, KeyFrame, ECurveInfinity, cubic_bezier_ease};
+
+    fn curve() -> FrameCurve<Number> {
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::linear(2., 10.));
+        curve.add_key(KeyFrame::step(0., 0.));
+        curve.add_key(KeyFrame::linear(1., 5.));
+        curve.add_key(KeyFrame::linear(3., 30.));
+        curve
+    }
+
+    #[test]
+    fn test_curve_sample() {
+        assert_eq!((Number::size(), Vector2::size(), Vector3::size(), Vector4::size()), (1, 2, 3, 4));
+
+        let curve = curve();
+        assert_eq!(curve.keys().iter().map(|key| key.time).collect::<Vec<_>>(), vec![0., 1., 2., 3.]);
+        assert!(FrameCurve::<Number>::new().sample(0.).is_none());
+        assert_relative_eq!(curve.sample(0.5).unwrap(), 0.);
+        assert_relative_eq!(curve.sample(1.5).unwrap(), 7.5);
+        assert_relative_eq!(curve.sample(3.).unwrap(), 30.);
+        assert_relative_eq!(curve.sample(-1.).unwrap(), 0.);
+        assert_relative_eq!(curve.sample(5.).unwrap(), 30.);
+
+        // 游标顺序 逆序 跳跃采样与二分结果一致
+        let mut cursor = 0;
+        for time in [0.1, 0.7, 1.2, 1.9, 2.5, 0.3, 2.9, 1.0, 2.0] {
+            assert_relative_eq!(curve.sample_with_cursor(time, &mut cursor).unwrap(), curve.sample(time).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_curve_interpolation() {
+        // Hermite: 切线与直线斜率一致时为直线
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::hermite(0., Vector3::new(0., 0., 0.), Vector3::zeros(), Vector3::new(2., 0., 0.)));
+        curve.add_key(KeyFrame::hermite(2., Vector3::new(4., 0., 0.), Vector3::new(2., 0., 0.), Vector3::zeros()));
+        assert_relative_eq!(curve.sample(0.5).unwrap(), Vector3::new(1., 0., 0.), epsilon = 0.0001);
+
+        // 贝塞尔缓动
+        assert_relative_eq!(cubic_bezier_ease(0., 0., 1., 1., 0.3), 0.3, epsilon = 0.0001);
+        assert_relative_eq!(cubic_bezier_ease(0.42, 0., 0.58, 1., 0.5), 0.5, epsilon = 0.0001);
+        assert!(cubic_bezier_ease(0.42, 0., 1., 1., 0.25) < 0.25);
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::cubic_bezier(0., 0., 0.42, 0., 1., 1.));
+        curve.add_key(KeyFrame::linear(1., 10.));
+        assert_relative_eq!(curve.sample(0.25).unwrap(), cubic_bezier_ease(0.42, 0., 1., 1., 0.25) * 10., epsilon = 0.0001);
+
+        // 旋转曲线走球面插值
+        let about_y = |angle: Number| Quaternion::from_axis_angle(&Vector3::y_axis(), angle).into_inner();
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::linear(0., about_y(0.)));
+        curve.add_key(KeyFrame::linear(1., about_y(2.)));
+        assert_relative_eq!(curve.sample(0.25).unwrap(), about_y(0.5), epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_curve_infinity() {
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::linear(1., 0.));
+        curve.add_key(KeyFrame::linear(3., 4.));
+
+        curve.pre_infinity = ECurveInfinity::Loop;
+        curve.post_infinity = ECurveInfinity::Loop;
+        assert_relative_eq!(curve.sample(3.5).unwrap(), 1.);
+        assert_relative_eq!(curve.sample(0.5).unwrap(), 3.);
+
+        curve.post_infinity = ECurveInfinity::PingPong;
+        curve.pre_infinity = ECurveInfinity::PingPong;
+        assert_relative_eq!(curve.sample(3.5).unwrap(), 3.);
+        assert_relative_eq!(curve.sample(5.5).unwrap(), 1.);
+        assert_relative_eq!(curve.sample(0.5).unwrap(), 1.);
+
+        curve.post_infinity = ECurveInfinity::RelativeRepeat;
+        curve.pre_infinity = ECurveInfinity::RelativeRepeat;
+        assert_relative_eq!(curve.sample(3.5).unwrap(), 5.);
+        assert_relative_eq!(curve.sample(7.5).unwrap(), 13.);
+        assert_relative_eq!(curve.sample(0.5).unwrap(), -1.);
+    }
+}
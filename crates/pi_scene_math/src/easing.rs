@@ -0,0 +1,211 @@
+use crate::{Number, consts::{PI, FRAC_PI_2}, curve::{TInterpolateVector, cubic_bezier_ease}};
+
+/// 缓动模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EEasingMode {
+    #[default]
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+/// 缓动函数 - 同 BabylonJS EasingFunction, 参数默认值见 `Easing` 的构造函数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EEasingFunction {
+    Sine,
+    Quad,
+    Cubic,
+    Quart,
+    Quint,
+    /// 指数
+    Expo { exponent: Number },
+    Circle,
+    /// 回退 - amplitude 越大回退越多
+    Back { amplitude: Number },
+    /// 弹跳 - bounces 次数, bounciness 相邻弹跳高度比
+    Bounce { bounces: Number, bounciness: Number },
+    /// 弹性 - oscillations 振荡次数, springiness 衰减
+    Elastic { oscillations: Number, springiness: Number },
+    Power { power: Number },
+    /// 三次贝塞尔 - 控制点 (x1, y1) (x2, y2)
+    BezierCurve { x1: Number, y1: Number, x2: Number, y2: Number },
+}
+
+impl EEasingFunction {
+    /// EaseIn 形式的缓动
+    fn ease_in_core(&self, gradient: Number) -> Number {
+        match *self {
+            EEasingFunction::Sine => 1. - (FRAC_PI_2 * (1. - gradient)).sin(),
+            EEasingFunction::Quad => gradient * gradient,
+            EEasingFunction::Cubic => gradient * gradient * gradient,
+            EEasingFunction::Quart => gradient.powi(4),
+            EEasingFunction::Quint => gradient.powi(5),
+            EEasingFunction::Expo { exponent } => {
+                if exponent <= 0. {
+                    gradient
+                } else {
+                    ((exponent * gradient).exp() - 1.) / (exponent.exp() - 1.)
+                }
+            },
+            EEasingFunction::Circle => {
+                let gradient = gradient.clamp(0., 1.);
+                1. - (1. - gradient * gradient).sqrt()
+            },
+            EEasingFunction::Back { amplitude } => {
+                gradient.powi(3) - gradient * amplitude.max(0.) * (PI * gradient).sin()
+            },
+            EEasingFunction::Bounce { bounces, bounciness } => Self::bounce(gradient, bounces, bounciness),
+            EEasingFunction::Elastic { oscillations, springiness } => {
+                let oscillations = oscillations.max(0.);
+                let springiness = springiness.max(0.);
+                let decay = if springiness == 0. {
+                    gradient
+                } else {
+                    ((springiness * gradient).exp() - 1.) / (springiness.exp() - 1.)
+                };
+                decay * ((2. * PI * oscillations + FRAC_PI_2) * gradient).sin()
+            },
+            EEasingFunction::Power { power } => gradient.powf(power.max(0.)),
+            EEasingFunction::BezierCurve { x1, y1, x2, y2 } => cubic_bezier_ease(x1, y1, x2, y2, gradient),
+        }
+    }
+
+    fn bounce(gradient: Number, bounces: Number, bounciness: Number) -> Number {
+        let bounces = bounces.max(0.);
+        let bounciness = if bounciness <= 1. { 1.001 } else { bounciness };
+        let pow = bounciness.powf(bounces);
+        let one_minus = 1. - bounciness;
+        let total = (1. - pow) / one_minus + pow * 0.5;
+        let position = gradient * total;
+        let bounce = ((-position * one_minus + 1.).ln() / bounciness.ln()).floor();
+        let start = (1. - bounciness.powf(bounce)) / (one_minus * total);
+        let end = (1. - bounciness.powf(bounce + 1.)) / (one_minus * total);
+        let middle = (start + end) * 0.5;
+        let offset = gradient - middle;
+        let radius = middle - start;
+        (-(1. / bounciness).powf(bounces - bounce) / (radius * radius)) * (offset - radius) * (offset + radius)
+    }
+}
+
+/// 缓动 - 将 [0, 1] 的插值比例映射为缓动后的比例
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Easing {
+    pub function: EEasingFunction,
+    pub mode: EEasingMode,
+}
+
+impl Easing {
+    pub fn new(function: EEasingFunction, mode: EEasingMode) -> Self {
+        Self { function, mode }
+    }
+    pub fn sine(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Sine, mode)
+    }
+    pub fn quad(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Quad, mode)
+    }
+    pub fn cubic(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Cubic, mode)
+    }
+    pub fn quart(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Quart, mode)
+    }
+    pub fn quint(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Quint, mode)
+    }
+    /// 默认 exponent 2
+    pub fn expo(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Expo { exponent: 2. }, mode)
+    }
+    pub fn circle(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Circle, mode)
+    }
+    /// 默认 amplitude 1
+    pub fn back(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Back { amplitude: 1. }, mode)
+    }
+    /// 默认 bounces 3, bounciness 2
+    pub fn bounce(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Bounce { bounces: 3., bounciness: 2. }, mode)
+    }
+    /// 默认 oscillations 3, springiness 3
+    pub fn elastic(mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Elastic { oscillations: 3., springiness: 3. }, mode)
+    }
+    pub fn power(power: Number, mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::Power { power }, mode)
+    }
+    pub fn bezier_curve(x1: Number, y1: Number, x2: Number, y2: Number, mode: EEasingMode) -> Self {
+        Self::new(EEasingFunction::BezierCurve { x1, y1, x2, y2 }, mode)
+    }
+
+    /// 缓动后的比例
+    pub fn ease(&self, gradient: Number) -> Number {
+        match self.mode {
+            EEasingMode::EaseIn => self.function.ease_in_core(gradient),
+            EEasingMode::EaseOut => 1. - self.function.ease_in_core(1. - gradient),
+            EEasingMode::EaseInOut => {
+                if gradient >= 0.5 {
+                    (1. - self.function.ease_in_core((1. - gradient) * 2.)) * 0.5 + 0.5
+                } else {
+                    self.function.ease_in_core(gradient * 2.) * 0.5
+                }
+            },
+        }
+    }
+
+    /// 以缓动后的比例插值
+    pub fn interpolate<T: TInterpolateVector>(&self, from: &T, to: &T, amount: Number) -> T {
+        from.interpolate(to, self.ease(amount))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, curve::{FrameCurve, KeyFrame}};
+
+    use super::{Easing, EEasingMode};
+
+    #[test]
+    fn test_easing() {
+        let modes = [EEasingMode::EaseIn, EEasingMode::EaseOut, EEasingMode::EaseInOut];
+        for mode in modes {
+            let easings = [
+                Easing::sine(mode), Easing::quad(mode), Easing::cubic(mode), Easing::quart(mode), Easing::quint(mode),
+                Easing::expo(mode), Easing::circle(mode), Easing::back(mode), Easing::bounce(mode), Easing::elastic(mode),
+                Easing::power(3., mode), Easing::bezier_curve(0.25, 0.1, 0.25, 1., mode),
+            ];
+            for easing in easings {
+                assert_relative_eq!(easing.ease(0.), 0., epsilon = 0.001);
+                assert_relative_eq!(easing.ease(1.), 1., epsilon = 0.001);
+            }
+        }
+
+        assert_relative_eq!(Easing::quad(EEasingMode::EaseIn).ease(0.5), 0.25);
+        assert_relative_eq!(Easing::quad(EEasingMode::EaseOut).ease(0.5), 0.75);
+        assert_relative_eq!(Easing::cubic(EEasingMode::EaseInOut).ease(0.25), 0.0625);
+        assert_relative_eq!(Easing::cubic(EEasingMode::EaseInOut).ease(0.75), 0.9375);
+        assert_relative_eq!(Easing::power(3., EEasingMode::EaseIn).ease(0.5), Easing::cubic(EEasingMode::EaseIn).ease(0.5));
+        // 回退先反向
+        assert!(Easing::back(EEasingMode::EaseIn).ease(0.3) < 0.);
+        // 弹跳不超过终点
+        for i in 0..=20 {
+            let value = Easing::bounce(EEasingMode::EaseOut).ease(i as crate::Number / 20.);
+            assert!((-0.0001..=1.0001).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_easing_interpolate() {
+        let easing = Easing::quad(EEasingMode::EaseIn);
+        let value = easing.interpolate(&Vector3::zeros(), &Vector3::new(4., 8., 0.), 0.5);
+        assert_relative_eq!(value, Vector3::new(1., 2., 0.));
+
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::eased(0., 0., easing));
+        curve.add_key(KeyFrame::linear(2., 10.));
+        assert_relative_eq!(curve.sample(1.).unwrap(), 2.5);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Number, Vector3, Isometry3, coordiante_system::CoordinateSytem3, vector::{TToolMatrix, TToolVector3}, camera::Camera};
+
+/// 自由飞行相机控制器 - 第一人称
+/// * yaw 绕坐标系向上轴, 正值向右转; pitch 绕相机右轴, 正值抬头
+/// * 移动输入为保持状态 (如按键按下), 速度按 acceleration 趋近目标速度
+/// * 每帧以时间步长调用 `update`
+#[derive(Debug, Clone)]
+pub struct FlyController {
+    coord: CoordinateSytem3,
+    pub position: Vector3,
+    pub yaw: Number,
+    pub pitch: Number,
+    /// pitch 绝对值上限, 默认略小于 PI/2
+    pub max_pitch: Number,
+    /// 最大移动速度 (单位/秒)
+    pub speed: Number,
+    /// 加速度 (单位/秒²), 加速与减速相同
+    pub acceleration: Number,
+    /// 视角增量缩放
+    pub look_sensitivity: Number,
+    velocity: Vector3,
+    /// 移动输入 前 右 上, 各分量 [-1, 1]
+    move_input: Vector3,
+}
+
+impl FlyController {
+    pub fn new(coord: CoordinateSytem3, position: Vector3) -> Self {
+        Self {
+            coord,
+            position,
+            yaw: 0.,
+            pitch: 0.,
+            max_pitch: crate::consts::FRAC_PI_2 - 0.01,
+            speed: 10.,
+            acceleration: 40.,
+            look_sensitivity: 1.,
+            velocity: Vector3::zeros(),
+            move_input: Vector3::zeros(),
+        }
+    }
+
+    pub fn coordinate_system(&self) -> &CoordinateSytem3 {
+        &self.coord
+    }
+
+    pub fn velocity(&self) -> Vector3 {
+        self.velocity
+    }
+
+    /// 视角增量 (弧度), 立即生效
+    pub fn look(&mut self, delta_yaw: Number, delta_pitch: Number) {
+        self.yaw += delta_yaw * self.look_sensitivity;
+        // max_pitch 可能被设为负数或 NaN, 不用 clamp 以免 panic
+        self.pitch = (self.pitch + delta_pitch * self.look_sensitivity).max(-self.max_pitch).min(self.max_pitch);
+    }
+
+    /// 设置移动输入 - 沿视线前进 沿相机右方 沿坐标系向上, 各分量 [-1, 1]
+    pub fn move_axis(&mut self, forward: Number, right: Number, up: Number) {
+        self.move_input = Vector3::new(forward, right, up);
+    }
+
+    /// 视线方向
+    pub fn forward(&self) -> Vector3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        // 参考坐标系 (左手 Y 向上) 下计算, 转换到控制器坐标系
+        self.coord.basis().transpose() * Vector3::new(sin_yaw * cos_pitch, sin_pitch, cos_yaw * cos_pitch)
+    }
+
+    /// 相机右方 (水平)
+    pub fn right(&self) -> Vector3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        self.coord.basis().transpose() * Vector3::new(cos_yaw, 0., -sin_yaw)
+    }
+
+    /// 按时间步长更新速度与位置
+    pub fn update(&mut self, delta_time: Number) {
+        let mut direction = self.forward() * self.move_input.x + self.right() * self.move_input.y + self.coord.up() * self.move_input.z;
+        let length = direction.norm();
+        if length > 1. {
+            direction /= length;
+        }
+
+        let difference = direction * self.speed - self.velocity;
+        let max_change = self.acceleration * delta_time;
+        let change = difference.norm();
+        if change > max_change {
+            self.velocity += difference * (max_change / change);
+        } else {
+            self.velocity += difference;
+        }
+
+        self.position += self.velocity * delta_time;
+    }
+
+    /// 视图变换
+    pub fn view(&self, result: &mut Isometry3) {
+        self.coord.lookat(&self.position, &(self.position + self.forward()), &self.coord.up(), result);
+    }
+
+    /// 设置相机视图
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.lookat(&self.position, &(self.position + self.forward()), &self.coord.up());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, coordiante_system::{CoordinateSytem3, ECoordinateSytem3, EUpAxis}, vector::TToolVector3, consts::FRAC_PI_2};
+
+    use super::FlyController;
+
+    #[test]
+    fn test_fly_look() {
+        let coords = [
+            CoordinateSytem3::left(),
+            CoordinateSytem3::right(),
+            CoordinateSytem3::new(ECoordinateSytem3::Right, EUpAxis::Z),
+            CoordinateSytem3::new(ECoordinateSytem3::Left, EUpAxis::Z),
+        ];
+        for coord in coords {
+            let mut fly = FlyController::new(coord, Vector3::zeros());
+            assert_relative_eq!(fly.forward(), coord.forward(), epsilon = 0.0001);
+
+            // 右转 90 度后视线朝向原右方
+            let right = fly.right();
+            fly.look(FRAC_PI_2, 0.);
+            assert_relative_eq!(fly.forward(), right, epsilon = 0.0001);
+
+            // 抬头被限制
+            fly.look(0., 10.);
+            assert_relative_eq!(fly.pitch, fly.max_pitch);
+            assert!(fly.forward().dot(&coord.up()) > 0.99);
+        }
+
+        // 上限为负或 NaN 时不 panic
+        let mut fly = FlyController::new(CoordinateSytem3::left(), Vector3::zeros());
+        fly.max_pitch = -1.;
+        fly.look(0., 0.5);
+        assert_relative_eq!(fly.pitch, -1.);
+        fly.max_pitch = Number::NAN;
+        fly.look(0., 0.5);
+        assert_relative_eq!(fly.pitch, -0.5);
+    }
+
+    #[test]
+    fn test_fly_move() {
+        let mut fly = FlyController::new(CoordinateSytem3::right(), Vector3::zeros());
+        fly.speed = 10.;
+        fly.acceleration = 20.;
+        fly.move_axis(1., 0., 0.);
+
+        fly.update(0.1);
+        assert_relative_eq!(fly.velocity(), Vector3::new(0., 0., -2.), epsilon = 0.0001);
+        assert_relative_eq!(fly.position, Vector3::new(0., 0., -0.2), epsilon = 0.0001);
+
+        // 加速到最大速度后匀速
+        for _ in 0..10 {
+            fly.update(0.1);
+        }
+        assert_relative_eq!(fly.velocity().norm(), 10., epsilon = 0.0001);
+
+        // 松开后减速停止
+        fly.move_axis(0., 0., 0.);
+        for _ in 0..10 {
+            fly.update(0.1);
+        }
+        assert_relative_eq!(fly.velocity().norm(), 0., epsilon = 0.0001);
+
+        // 对角移动不超过最大速度
+        fly.move_axis(1., 1., 1.);
+        for _ in 0..10 {
+            fly.update(0.1);
+        }
+        assert_relative_eq!(fly.velocity().norm(), 10., epsilon = 0.0001);
+    }
+}
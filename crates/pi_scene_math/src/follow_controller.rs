@@ -0,0 +1,143 @@
+use crate::{Number, Vector3, Isometry3, coordiante_system::CoordinateSytem3, vector::{TToolMatrix, TToolVector3}, transform::Transform3, camera::Camera};
+
+/// 跟随相机控制器 - 追随目标 Transform3
+/// * 期望位置 = 目标位置 + 目标旋转 * offset
+/// * lag 为平滑时间常数 (秒), 0 为刚性跟随
+/// * look_ahead 为前瞻时间 (秒), 注视点沿目标速度方向前移
+/// * 每帧以时间步长调用 `update`, 首次更新直接就位
+#[derive(Debug, Clone)]
+pub struct FollowController {
+    coord: CoordinateSytem3,
+    /// 目标局部空间下的相机偏移
+    pub offset: Vector3,
+    pub lag: Number,
+    pub look_ahead: Number,
+    position: Vector3,
+    look_target: Vector3,
+    last_target: Option<Vector3>,
+}
+
+impl FollowController {
+    pub fn new(coord: CoordinateSytem3, offset: Vector3) -> Self {
+        Self {
+            coord,
+            offset,
+            lag: 0.2,
+            look_ahead: 0.,
+            position: Vector3::zeros(),
+            look_target: Vector3::zeros(),
+            last_target: None,
+        }
+    }
+
+    pub fn coordinate_system(&self) -> &CoordinateSytem3 {
+        &self.coord
+    }
+
+    pub fn position(&self) -> Vector3 {
+        self.position
+    }
+
+    pub fn look_target(&self) -> Vector3 {
+        self.look_target
+    }
+
+    /// 下次更新直接就位
+    pub fn reset(&mut self) {
+        self.last_target = None;
+    }
+
+    /// 按时间步长追随目标
+    pub fn update(&mut self, target: &Transform3, delta_time: Number) {
+        let translation = target.translation();
+        let desired = translation + target.rotation_matrix() * self.offset;
+
+        match self.last_target {
+            Some(last) if delta_time > 0. => {
+                let velocity = (translation - last) / delta_time;
+                let look_target = translation + velocity * self.look_ahead;
+                let factor = Self::smooth_factor(self.lag, delta_time);
+                self.position += (desired - self.position) * factor;
+                self.look_target += (look_target - self.look_target) * factor;
+            },
+            Some(_) => {},
+            None => {
+                self.position = desired;
+                self.look_target = translation;
+            },
+        }
+
+        self.last_target = Some(translation);
+    }
+
+    /// 视图变换
+    pub fn view(&self, result: &mut Isometry3) {
+        self.coord.lookat(&self.position, &self.look_target, &self.coord.up(), result);
+    }
+
+    /// 设置相机视图
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.lookat(&self.position, &self.look_target, &self.coord.up());
+    }
+
+    /// 指数平滑系数, 与帧率无关
+    fn smooth_factor(lag: Number, delta_time: Number) -> Number {
+        if lag <= 0. {
+            1.
+        } else {
+            1. - (-delta_time / lag).exp()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Rotation3, coordiante_system::CoordinateSytem3, transform::Transform3, consts::FRAC_PI_2};
+
+    use super::FollowController;
+
+    #[test]
+    fn test_follow() {
+        let mut target = Transform3::default();
+        target.set_translation_from_floats(10., 0., 0.);
+        target.rotation_matrix_mut(&Rotation3::from_axis_angle(&Vector3::y_axis(), FRAC_PI_2));
+
+        let mut follow = FollowController::new(CoordinateSytem3::left(), Vector3::new(0., 2., -5.));
+        follow.lag = 0.;
+        follow.update(&target, 0.1);
+        assert_relative_eq!(follow.position(), Vector3::new(5., 2., 0.), epsilon = 0.0001);
+        assert_relative_eq!(follow.look_target(), Vector3::new(10., 0., 0.), epsilon = 0.0001);
+
+        // 平滑跟随: 两次半步与一次整步结果相同
+        follow.lag = 0.5;
+        let mut other = follow.clone();
+        target.set_translation_from_floats(12., 0., 0.);
+        follow.update(&target, 0.1);
+        follow.update(&target, 0.1);
+        other.update(&target, 0.2);
+        assert_relative_eq!(follow.position(), other.position(), epsilon = 0.0001);
+        assert!(follow.position().x > 5. && follow.position().x < 7.);
+
+        for _ in 0..200 {
+            follow.update(&target, 0.1);
+        }
+        assert_relative_eq!(follow.position(), Vector3::new(7., 2., 0.), epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_follow_look_ahead() {
+        let mut target = Transform3::default();
+        let mut follow = FollowController::new(CoordinateSytem3::right(), Vector3::new(0., 2., 5.));
+        follow.lag = 0.;
+        follow.look_ahead = 0.5;
+        follow.update(&target, 0.1);
+
+        // 目标以 10 单位/秒沿 -Z 移动, 注视点前移 5
+        target.set_translation_from_floats(0., 0., -1.);
+        follow.update(&target, 0.1);
+        assert_relative_eq!(follow.look_target(), Vector3::new(0., 0., -6.), epsilon = 0.0001);
+        assert_relative_eq!(follow.position(), Vector3::new(0., 2., 4.), epsilon = 0.0001);
+    }
+}
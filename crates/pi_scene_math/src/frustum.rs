@@ -1,5 +1,25 @@
-use crate::{plane::Plane, Matrix};
+use crate::{plane::Plane, Matrix, Number, Vector3, coordiante_system::EDepthRange};
 
+/// 几何体与视锥的包含关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EContainment {
+    /// 完全在视锥外
+    Outside,
+    /// 与视锥相交
+    Intersecting,
+    /// 完全在视锥内
+    Inside,
+}
+
+/// 视锥的 12 条棱 - 角点索引见 `FrustumPlanes::corners`
+const FRUSTUM_EDGES: [(usize, usize); 12] = [
+    (0, 1), (1, 2), (2, 3), (3, 0),
+    (4, 5), (5, 6), (6, 7), (7, 4),
+    (0, 4), (1, 5), (2, 6), (3, 7),
+];
+
+/// 六个平面法线均指向视锥内部
+#[derive(Debug, Clone, Copy, Default)]
 pub struct FrustumPlanes {
     pub near: Plane,
     pub far: Plane,
@@ -9,17 +29,14 @@ pub struct FrustumPlanes {
     pub bottom: Plane,
 }
 
-impl Default for FrustumPlanes {
-    fn default() -> Self {
-        Self { near: Plane::default(), far: Plane::default(), left: Plane::default(), right: Plane::default(), top: Plane::default(), bottom: Plane::default() }
-    }
-}
-
 impl FrustumPlanes {
     /// 使用的 BABYLONJS 代码 行主序
-    pub fn from_transform_matrix(&mut self, transform: &Matrix) {
+    /// 从视图投影矩阵提取六个平面, depth_range 为投影输出的深度范围
+    /// * 反向深度投影提取的 near 与 far 互换 - 即 near 为远平面, 包含测试不受影响
+    /// * 无限远反向深度投影的 near 为无穷远平面, 此时 `corners` 返回 false
+    pub fn from_transform_matrix(&mut self, transform: &Matrix, depth_range: EDepthRange) {
         // Near
-        self.transform_near_plane(transform);
+        self.transform_near_plane(transform, depth_range);
 
         // Far
         self.transform_far_plane(transform);
@@ -37,13 +54,28 @@ impl FrustumPlanes {
         self.transform_bottom_plane(transform);
     }
 
-    pub fn transform_near_plane(&mut self, transform: &Matrix) {
+    /// [-1, 1]: z >= -w, [0, 1]: z >= 0
+    pub fn transform_near_plane(&mut self, transform: &Matrix, depth_range: EDepthRange) {
         let m = transform;
-        self.near.normal.x  = m[3]  + m[2];
-        self.near.normal.y  = m[7]  + m[6];
-        self.near.normal.z  = m[11] + m[10];
-        self.near.d         = m[15] + m[14];
+        match depth_range {
+            EDepthRange::NegativeOneToOne => {
+                self.near.normal.x  = m[3]  + m[2];
+                self.near.normal.y  = m[7]  + m[6];
+                self.near.normal.z  = m[11] + m[10];
+                self.near.d         = m[15] + m[14];
+            },
+            EDepthRange::ZeroToOne => {
+                self.near.normal.x  = m[2];
+                self.near.normal.y  = m[6];
+                self.near.normal.z  = m[10];
+                self.near.d         = m[14];
+            },
+        }
         self.near.normalize();
+        // 无限远反向深度时为无穷远平面, 视为恒在内侧
+        if self.near.normal == Vector3::zeros() {
+            self.near.d = Number::MAX;
+        }
     }
 
     pub fn transform_far_plane(&mut self, transform: &Matrix) {
@@ -53,6 +85,10 @@ impl FrustumPlanes {
         self.far.normal.z = m[11] - m[10];
         self.far.d = m[15] - m[14];
         self.far.normalize();
+        // 无限远标准深度时为无穷远平面, 视为恒在内侧
+        if self.far.normal == Vector3::zeros() {
+            self.far.d = Number::MAX;
+        }
     }
 
     pub fn transform_left_plane(&mut self, transform: &Matrix) {
@@ -90,4 +126,342 @@ impl FrustumPlanes {
         self.bottom.d = m[15] + m[13];
         self.bottom.normalize();
     }
+
+    pub fn planes(&self) -> [&Plane; 6] {
+        [&self.near, &self.far, &self.left, &self.right, &self.top, &self.bottom]
+    }
+
+    /// 视锥 8 个角点: 0-3 近平面, 4-7 远平面, 各按 左下 右下 右上 左上 排列
+    /// * 平面退化 (如远平面在无穷远) 时返回 false
+    pub fn corners(&self, result: &mut [Vector3; 8]) -> bool {
+        let sides = [
+            (&self.left, &self.bottom),
+            (&self.right, &self.bottom),
+            (&self.right, &self.top),
+            (&self.left, &self.top),
+        ];
+        for (i, (horizontal, vertical)) in sides.iter().enumerate() {
+            if !intersect_three_planes(&self.near, horizontal, vertical, &mut result[i]) {
+                return false;
+            }
+            if !intersect_three_planes(&self.far, horizontal, vertical, &mut result[i + 4]) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 点与视锥的关系 - 仅 Inside / Outside, 恰在某平面上时为 Intersecting
+    /// * 平面测试对点本身即是精确的
+    pub fn contains_point(&self, point: &Vector3) -> EContainment {
+        let mut result = EContainment::Inside;
+        for plane in self.planes() {
+            let distance = plane.dot_coordinate2(point);
+            if distance < 0. {
+                return EContainment::Outside;
+            } else if distance == 0. {
+                result = EContainment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// 球与视锥的关系 - 快速保守测试
+    /// * 在视锥角落附近可能把外部的球判为 Intersecting, 不会漏判
+    pub fn intersects_sphere(&self, center: &Vector3, radius: Number) -> EContainment {
+        let mut result = EContainment::Inside;
+        for plane in self.planes() {
+            let distance = plane.dot_coordinate2(center);
+            if distance < -radius {
+                return EContainment::Outside;
+            } else if distance < radius {
+                result = EContainment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// 球与视锥的关系 - 精确测试, 计算球心到视锥表面的最近距离
+    pub fn intersects_sphere_exact(&self, center: &Vector3, radius: Number) -> EContainment {
+        let result = self.intersects_sphere(center, radius);
+        if result != EContainment::Intersecting {
+            return result;
+        }
+
+        let planes = self.planes();
+        if planes.iter().all(|plane| plane.dot_coordinate2(center) >= 0.) {
+            return EContainment::Intersecting;
+        }
+
+        let mut corners = [Vector3::zeros(); 8];
+        if !self.corners(&mut corners) {
+            return result;
+        }
+
+        let radius_squared = radius * radius;
+        // 最近点落在某个面内部
+        for (i, plane) in planes.iter().enumerate() {
+            let distance = plane.dot_coordinate2(center);
+            if distance < 0. && -distance <= radius {
+                let projected = center - plane.normal * distance;
+                let on_face = planes.iter().enumerate().all(|(j, other)| {
+                    i == j || other.dot_coordinate2(&projected) >= -Number::EPSILON
+                });
+                if on_face {
+                    return EContainment::Intersecting;
+                }
+            }
+        }
+        // 最近点落在棱或角点上
+        for (a, b) in FRUSTUM_EDGES.iter() {
+            let start = &corners[*a];
+            let edge = corners[*b] - start;
+            let length_squared = edge.norm_squared();
+            let amount = if length_squared > 0. {
+                ((center - start).dot(&edge) / length_squared).clamp(0., 1.)
+            } else {
+                0.
+            };
+            let closest = start + edge * amount;
+            if (center - closest).norm_squared() <= radius_squared {
+                return EContainment::Intersecting;
+            }
+        }
+
+        EContainment::Outside
+    }
+
+    /// 轴对齐包围盒与视锥的关系 - 快速保守测试 (p-vertex / n-vertex)
+    pub fn intersects_aabb(&self, minimum: &Vector3, maximum: &Vector3) -> EContainment {
+        let mut result = EContainment::Inside;
+        for plane in self.planes() {
+            let n = &plane.normal;
+            let positive = Vector3::new(
+                if n.x >= 0. { maximum.x } else { minimum.x },
+                if n.y >= 0. { maximum.y } else { minimum.y },
+                if n.z >= 0. { maximum.z } else { minimum.z },
+            );
+            if plane.dot_coordinate2(&positive) < 0. {
+                return EContainment::Outside;
+            }
+            let negative = Vector3::new(
+                if n.x >= 0. { minimum.x } else { maximum.x },
+                if n.y >= 0. { minimum.y } else { maximum.y },
+                if n.z >= 0. { minimum.z } else { maximum.z },
+            );
+            if plane.dot_coordinate2(&negative) < 0. {
+                result = EContainment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// 轴对齐包围盒与视锥的关系 - 精确测试 (分离轴)
+    pub fn intersects_aabb_exact(&self, minimum: &Vector3, maximum: &Vector3) -> EContainment {
+        let result = self.intersects_aabb(minimum, maximum);
+        if result != EContainment::Intersecting {
+            return result;
+        }
+
+        let center = (minimum + maximum) * 0.5;
+        let extents = (maximum - minimum) * 0.5;
+        let axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+        if self.is_separated_from_box(&center, &axes, &extents) {
+            EContainment::Outside
+        } else {
+            EContainment::Intersecting
+        }
+    }
+
+    /// 有向包围盒与视锥的关系 - 快速保守测试
+    /// * `axes` 包围盒三个单位方向轴
+    /// * `extents` 沿各轴的半长
+    pub fn intersects_obb(&self, center: &Vector3, axes: &[Vector3; 3], extents: &Vector3) -> EContainment {
+        let mut result = EContainment::Inside;
+        for plane in self.planes() {
+            let n = &plane.normal;
+            let radius = n.dot(&axes[0]).abs() * extents.x
+                + n.dot(&axes[1]).abs() * extents.y
+                + n.dot(&axes[2]).abs() * extents.z;
+            let distance = plane.dot_coordinate2(center);
+            if distance < -radius {
+                return EContainment::Outside;
+            } else if distance < radius {
+                result = EContainment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// 有向包围盒与视锥的关系 - 精确测试 (分离轴)
+    pub fn intersects_obb_exact(&self, center: &Vector3, axes: &[Vector3; 3], extents: &Vector3) -> EContainment {
+        let result = self.intersects_obb(center, axes, extents);
+        if result != EContainment::Intersecting {
+            return result;
+        }
+
+        if self.is_separated_from_box(center, axes, extents) {
+            EContainment::Outside
+        } else {
+            EContainment::Intersecting
+        }
+    }
+
+    /// 视锥与盒子的分离轴测试: 面法线 + 盒子轴 + 两者棱方向的叉积
+    fn is_separated_from_box(&self, center: &Vector3, axes: &[Vector3; 3], extents: &Vector3) -> bool {
+        let mut corners = [Vector3::zeros(); 8];
+        if !self.corners(&mut corners) {
+            return false;
+        }
+
+        let separated = |axis: &Vector3| -> bool {
+            let length_squared = axis.norm_squared();
+            if length_squared < Number::EPSILON {
+                return false;
+            }
+            let mut frustum_min = Number::MAX;
+            let mut frustum_max = Number::MIN;
+            for corner in corners.iter() {
+                let v = corner.dot(axis);
+                frustum_min = frustum_min.min(v);
+                frustum_max = frustum_max.max(v);
+            }
+            let box_center = center.dot(axis);
+            let box_radius = axis.dot(&axes[0]).abs() * extents.x
+                + axis.dot(&axes[1]).abs() * extents.y
+                + axis.dot(&axes[2]).abs() * extents.z;
+            box_center + box_radius < frustum_min || box_center - box_radius > frustum_max
+        };
+
+        for plane in self.planes() {
+            if separated(&plane.normal) {
+                return true;
+            }
+        }
+        for axis in axes.iter() {
+            if separated(axis) {
+                return true;
+            }
+        }
+
+        let edges = [
+            corners[4] - corners[0],
+            corners[5] - corners[1],
+            corners[6] - corners[2],
+            corners[7] - corners[3],
+            corners[1] - corners[0],
+            corners[3] - corners[0],
+        ];
+        for axis in axes.iter() {
+            for edge in edges.iter() {
+                if separated(&axis.cross(edge)) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// 三平面交点
+fn intersect_three_planes(p1: &Plane, p2: &Plane, p3: &Plane, result: &mut Vector3) -> bool {
+    let n23 = p2.normal.cross(&p3.normal);
+    let det = p1.normal.dot(&n23);
+    if det.abs() < Number::EPSILON {
+        return false;
+    }
+    let n31 = p3.normal.cross(&p1.normal);
+    let n12 = p1.normal.cross(&p2.normal);
+    result.copy_from(&((n23 * p1.d + n31 * p2.d + n12 * p3.d) / -det));
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Matrix, Vector3, Perspective3, Point3, Isometry3, Rotation3};
+
+    use super::{FrustumPlanes, EContainment};
+    use crate::camera::{EDepthRange, TPerspectiveCameraTool};
+    use crate::coordiante_system::CoordinateSytem3;
+
+    /// 相机位于原点看向 -Z, near 1, far 100, 90° 视角
+    fn frustum() -> FrustumPlanes {
+        let projection = Perspective3::new(1., crate::consts::FRAC_PI_2, 1., 100.).to_homogeneous();
+        let view: Matrix = Isometry3::look_at_rh(&Point3::origin(), &Point3::new(0., 0., -1.), &Vector3::y()).to_homogeneous();
+        let mut frustum = FrustumPlanes::default();
+        frustum.from_transform_matrix(&(projection * view), EDepthRange::NegativeOneToOne);
+        frustum
+    }
+
+    #[test]
+    fn test_frustum_depth_range() {
+        let view: Matrix = Isometry3::look_at_rh(&Point3::origin(), &Point3::new(0., 0., -1.), &Vector3::y()).to_homogeneous();
+        for range in [EDepthRange::ZeroToOne, EDepthRange::NegativeOneToOne] {
+            let standard = CoordinateSytem3::perspective_rh(crate::consts::FRAC_PI_2, 1., 1., 100., true, range);
+            let reverse = CoordinateSytem3::perspective_for_reverse_rh(crate::consts::FRAC_PI_2, 1., 1., 100., true, range);
+            for projection in [standard, reverse] {
+                let mut frustum = FrustumPlanes::default();
+                frustum.from_transform_matrix(&(projection * view), range);
+                assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -0.9)), EContainment::Outside);
+                assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -1.1)), EContainment::Inside);
+                assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -99.)), EContainment::Inside);
+                assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -101.)), EContainment::Outside);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frustum_point() {
+        let frustum = frustum();
+        assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -10.)), EContainment::Inside);
+        assert_eq!(frustum.contains_point(&Vector3::new(0., 0., 10.)), EContainment::Outside);
+        assert_eq!(frustum.contains_point(&Vector3::new(0., 0., -0.5)), EContainment::Outside);
+        assert_eq!(frustum.contains_point(&Vector3::new(20., 0., -10.)), EContainment::Outside);
+    }
+
+    #[test]
+    fn test_frustum_sphere() {
+        let frustum = frustum();
+        assert_eq!(frustum.intersects_sphere(&Vector3::new(0., 0., -10.), 1.), EContainment::Inside);
+        assert_eq!(frustum.intersects_sphere(&Vector3::new(0., 0., -100.), 1.), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_sphere(&Vector3::new(0., 0., 10.), 1.), EContainment::Outside);
+
+        // 位于近平面角落外侧: 快速测试误判为相交, 精确测试判为外部
+        let center = Vector3::new(-2.5, 2.5, -0.5);
+        assert_eq!(frustum.intersects_sphere(&center, 1.5), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_sphere_exact(&center, 1.5), EContainment::Outside);
+        assert_eq!(frustum.intersects_sphere_exact(&Vector3::new(0., 0., -100.), 1.), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_sphere_exact(&Vector3::new(-1.05, 1.05, -0.95), 0.1), EContainment::Intersecting);
+    }
+
+    #[test]
+    fn test_frustum_aabb() {
+        let frustum = frustum();
+        assert_eq!(frustum.intersects_aabb(&Vector3::new(-1., -1., -11.), &Vector3::new(1., 1., -9.)), EContainment::Inside);
+        assert_eq!(frustum.intersects_aabb(&Vector3::new(-1., -1., -101.), &Vector3::new(1., 1., -99.)), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_aabb(&Vector3::new(-1., -1., 9.), &Vector3::new(1., 1., 11.)), EContainment::Outside);
+
+        // 远平面左侧棱外的盒子
+        let minimum = Vector3::new(-104., -1., -102.);
+        let maximum = Vector3::new(-101., 1., -98.);
+        assert_eq!(frustum.intersects_aabb(&minimum, &maximum), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_aabb_exact(&minimum, &maximum), EContainment::Outside);
+    }
+
+    #[test]
+    fn test_frustum_obb() {
+        let frustum = frustum();
+        let rotation = Rotation3::from_axis_angle(&Vector3::y_axis(), 0.6);
+        let axes = [rotation * Vector3::x(), rotation * Vector3::y(), rotation * Vector3::z()];
+        let extents = Vector3::new(1., 1., 1.);
+        assert_eq!(frustum.intersects_obb(&Vector3::new(0., 0., -10.), &axes, &extents), EContainment::Inside);
+        assert_eq!(frustum.intersects_obb(&Vector3::new(0., 0., 10.), &axes, &extents), EContainment::Outside);
+        assert_eq!(frustum.intersects_obb_exact(&Vector3::new(0., 0., -1.), &axes, &extents), EContainment::Intersecting);
+
+        let identity = [Vector3::x(), Vector3::y(), Vector3::z()];
+        let center = Vector3::new(-102.5, 0., -100.);
+        let extents = Vector3::new(1.5, 1., 2.);
+        assert_eq!(frustum.intersects_obb(&center, &identity, &extents), EContainment::Intersecting);
+        assert_eq!(frustum.intersects_obb_exact(&center, &identity, &extents), EContainment::Outside);
+    }
 }
@@ -0,0 +1,314 @@
+use crate::{Matrix, coordiante_system::CoordinateSytem3, vector::TToolMatrix, transform::Transform3};
+
+/// 节点句柄 - 数组下标 + 代数, 节点删除后旧句柄失效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+/// 重设父节点时保持的变换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EReparentMode {
+    /// 保持局部变换, 世界变换随新父节点变化
+    KeepLocal,
+    /// 保持世界变换, 重新计算局部变换 (含切变时切变丢失)
+    KeepWorld,
+}
+
+#[derive(Debug)]
+struct Node {
+    transform: Transform3,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    world_matrix: Matrix,
+    /// 局部变换修改后尚未更新世界矩阵
+    dirty: bool,
+    generation: u32,
+    alive: bool,
+}
+
+/// 场景层级 - 节点存储于数组, 以 NodeId 索引
+/// * 世界矩阵 = 父节点世界矩阵 * 局部矩阵
+/// * 修改局部变换只标记该节点, `update` 自根节点深度优先遍历, 父节点更新时其子树一并更新
+#[derive(Debug, Default)]
+pub struct Hierarchy {
+    nodes: Vec<Node>,
+    free: Vec<u32>,
+    roots: Vec<NodeId>,
+}
+
+impl Hierarchy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 存活节点数量
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    /// 添加节点, parent 无效时返回 None
+    pub fn insert(&mut self, transform: Transform3, parent: Option<NodeId>) -> Option<NodeId> {
+        if let Some(parent) = parent {
+            if !self.contains(parent) {
+                return None;
+            }
+        }
+
+        let node = Node {
+            transform,
+            parent,
+            children: Vec::new(),
+            world_matrix: Matrix::identity(),
+            dirty: true,
+            generation: 0,
+            alive: true,
+        };
+        let id = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.nodes[index as usize];
+                let generation = slot.generation + 1;
+                *slot = Node { generation, ..node };
+                NodeId { index, generation }
+            },
+            None => {
+                self.nodes.push(node);
+                NodeId { index: (self.nodes.len() - 1) as u32, generation: 0 }
+            },
+        };
+
+        match parent {
+            Some(parent) => self.nodes[parent.index as usize].children.push(id),
+            None => self.roots.push(id),
+        }
+        Some(id)
+    }
+
+    /// 删除节点及其子树
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        self.detach(id);
+
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let node = &mut self.nodes[current.index as usize];
+            node.alive = false;
+            node.parent = None;
+            stack.append(&mut node.children);
+            self.free.push(current.index);
+        }
+        true
+    }
+
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).and_then(|node| node.parent)
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match self.node(id) {
+            Some(node) => &node.children,
+            None => &[],
+        }
+    }
+
+    pub fn transform(&self, id: NodeId) -> Option<&Transform3> {
+        self.node(id).map(|node| &node.transform)
+    }
+
+    /// 可修改的局部变换, 节点标记为脏
+    pub fn transform_mut(&mut self, id: NodeId) -> Option<&mut Transform3> {
+        let node = self.node_mut(id)?;
+        node.dirty = true;
+        Some(&mut node.transform)
+    }
+
+    /// 世界矩阵 - `update` 之后有效
+    pub fn world_matrix(&self, id: NodeId) -> Option<&Matrix> {
+        self.node(id).map(|node| &node.world_matrix)
+    }
+
+    /// 节点或其祖先有修改尚未 `update`
+    pub fn is_dirty(&self, id: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            match self.node(id) {
+                Some(node) if node.dirty => return true,
+                Some(node) => current = node.parent,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// 重设父节点, parent 为 None 时成为根节点
+    /// * parent 为自身或其子孙 或句柄无效时返回 false
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>, mode: EReparentMode) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        if let Some(parent) = parent {
+            if !self.contains(parent) || self.is_ancestor_or_self(id, parent) {
+                return false;
+            }
+        }
+
+        if mode == EReparentMode::KeepWorld {
+            self.update();
+            let mut local = self.nodes[id.index as usize].world_matrix;
+            if let Some(parent) = parent {
+                let mut inverse = self.nodes[parent.index as usize].world_matrix;
+                if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+                    return false;
+                }
+                local = inverse * local;
+            }
+            self.nodes[id.index as usize].transform.set_from_matrix(&local);
+        }
+
+        self.detach(id);
+        let node = &mut self.nodes[id.index as usize];
+        node.parent = parent;
+        node.dirty = true;
+        match parent {
+            Some(parent) => self.nodes[parent.index as usize].children.push(id),
+            None => self.roots.push(id),
+        }
+        true
+    }
+
+    /// 自根节点深度优先更新脏节点及其子树的世界矩阵
+    pub fn update(&mut self) {
+        let mut stack: Vec<(NodeId, bool)> = self.roots.iter().rev().map(|id| (*id, false)).collect();
+        while let Some((id, parent_changed)) = stack.pop() {
+            let index = id.index as usize;
+            let changed = parent_changed || self.nodes[index].dirty;
+            if changed {
+                let parent_world = self.nodes[index].parent.map(|parent| self.nodes[parent.index as usize].world_matrix);
+                let node = &mut self.nodes[index];
+                node.transform.calc_matrix();
+                node.world_matrix = match parent_world {
+                    Some(parent_world) => parent_world * node.transform.matrix(),
+                    None => *node.transform.matrix(),
+                };
+                node.dirty = false;
+            }
+            stack.extend(self.nodes[index].children.iter().rev().map(|child| (*child, changed)));
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.index as usize).filter(|node| node.alive && node.generation == id.generation)
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id.index as usize).filter(|node| node.alive && node.generation == id.generation)
+    }
+
+    /// ancestor 是否为 id 自身或其祖先
+    fn is_ancestor_or_self(&self, ancestor: NodeId, id: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.parent(id);
+        }
+        false
+    }
+
+    /// 从父节点的子节点列表或根列表中移除
+    fn detach(&mut self, id: NodeId) {
+        let siblings = match self.nodes[id.index as usize].parent {
+            Some(parent) => &mut self.nodes[parent.index as usize].children,
+            None => &mut self.roots,
+        };
+        siblings.retain(|sibling| *sibling != id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Rotation3, transform::{Transform3, translated}, consts::FRAC_PI_2};
+
+    use super::{Hierarchy, EReparentMode};
+
+    fn world_position(hierarchy: &Hierarchy, id: super::NodeId) -> Vector3 {
+        hierarchy.world_matrix(id).unwrap().fixed_view::<3, 1>(0, 3).into_owned()
+    }
+
+    #[test]
+    fn test_hierarchy_update() {
+        let mut hierarchy = Hierarchy::new();
+        let root = hierarchy.insert(translated(10., 0., 0.), None).unwrap();
+        let child = hierarchy.insert(translated(0., 5., 0.), Some(root)).unwrap();
+        let grandchild = hierarchy.insert(translated(0., 0., 1.), Some(child)).unwrap();
+        hierarchy.update();
+        assert_relative_eq!(world_position(&hierarchy, grandchild), Vector3::new(10., 5., 1.));
+
+        // 修改父节点, 子树在 update 后更新
+        hierarchy.transform_mut(root).unwrap().rotation_matrix_mut(&Rotation3::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2));
+        assert!(hierarchy.is_dirty(grandchild));
+        hierarchy.update();
+        assert!(!hierarchy.is_dirty(grandchild));
+        assert_relative_eq!(world_position(&hierarchy, child), Vector3::new(5., 0., 0.), epsilon = 0.0001);
+        assert_relative_eq!(world_position(&hierarchy, grandchild), Vector3::new(5., 0., 1.), epsilon = 0.0001);
+
+        // 删除子树, 旧句柄失效, 槽位复用
+        assert!(hierarchy.remove(child));
+        assert!(!hierarchy.contains(grandchild));
+        assert!(hierarchy.children(root).is_empty());
+        assert_eq!(hierarchy.len(), 1);
+        let other = hierarchy.insert(Transform3::default(), Some(root)).unwrap();
+        assert!(!hierarchy.contains(child));
+        assert!(hierarchy.transform_mut(child).is_none());
+        assert_eq!(hierarchy.children(root), &[other]);
+        assert!(hierarchy.insert(Transform3::default(), Some(grandchild)).is_none());
+    }
+
+    #[test]
+    fn test_hierarchy_reparent() {
+        let mut hierarchy = Hierarchy::new();
+        let a = hierarchy.insert(translated(10., 0., 0.), None).unwrap();
+        let mut scaled = translated(0., 0., 5.);
+        scaled.set_scaling_uniform(2.);
+        let b = hierarchy.insert(scaled, None).unwrap();
+        let child = hierarchy.insert(translated(1., 0., 0.), Some(a)).unwrap();
+        hierarchy.update();
+
+        // 不能成为自身子孙的子节点
+        assert!(!hierarchy.set_parent(a, Some(child), EReparentMode::KeepLocal));
+        assert!(!hierarchy.set_parent(a, Some(a), EReparentMode::KeepLocal));
+
+        // 保持世界变换
+        assert!(hierarchy.set_parent(child, Some(b), EReparentMode::KeepWorld));
+        hierarchy.update();
+        assert_eq!(hierarchy.parent(child), Some(b));
+        assert!(hierarchy.children(a).is_empty());
+        assert_relative_eq!(world_position(&hierarchy, child), Vector3::new(11., 0., 0.), epsilon = 0.0001);
+        assert_relative_eq!(hierarchy.transform(child).unwrap().translation(), Vector3::new(5.5, 0., -2.5), epsilon = 0.0001);
+
+        // 保持局部变换
+        assert!(hierarchy.set_parent(child, None, EReparentMode::KeepLocal));
+        hierarchy.update();
+        assert_eq!(hierarchy.roots().len(), 3);
+        assert_relative_eq!(world_position(&hierarchy, child), Vector3::new(5.5, 0., -2.5), epsilon = 0.0001);
+        assert_relative_eq!(*hierarchy.world_matrix(child).unwrap(), *hierarchy.transform(child).unwrap().matrix(), epsilon = 0.0001);
+    }
+}
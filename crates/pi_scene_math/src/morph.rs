@@ -0,0 +1,236 @@
+use crate::{Number, Vector3, Vector4};
+
+/// 变形目标 - 相对基础网格的逐顶点增量
+#[derive(Debug, Clone)]
+pub struct MorphTarget {
+    pub name: String,
+    positions: Vec<Vector3>,
+    normals: Option<Vec<Vector3>>,
+    tangents: Option<Vec<Vector3>>,
+}
+
+impl MorphTarget {
+    pub fn new(name: &str, positions: Vec<Vector3>) -> Self {
+        Self { name: String::from(name), positions, normals: None, tangents: None }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn positions(&self) -> &[Vector3] {
+        &self.positions
+    }
+
+    pub fn normals(&self) -> Option<&[Vector3]> {
+        self.normals.as_deref()
+    }
+
+    pub fn tangents(&self) -> Option<&[Vector3]> {
+        self.tangents.as_deref()
+    }
+
+    /// 设置法线增量, 数量与顶点数不符时返回 false
+    pub fn set_normals(&mut self, normals: Option<Vec<Vector3>>) -> bool {
+        if normals.as_ref().is_some_and(|normals| normals.len() != self.positions.len()) {
+            return false;
+        }
+        self.normals = normals;
+        true
+    }
+
+    /// 设置切线增量, 数量与顶点数不符时返回 false
+    pub fn set_tangents(&mut self, tangents: Option<Vec<Vector3>>) -> bool {
+        if tangents.as_ref().is_some_and(|tangents| tangents.len() != self.positions.len()) {
+            return false;
+        }
+        self.tangents = tangents;
+        true
+    }
+}
+
+/// 网格的变形目标集合与权重
+/// * 结果 = 基础值 + Σ 权重 * 增量, 法线 切线混合后归一化
+/// * 权重可由动画浮点通道驱动: 通道 target 为变形目标下标, `AnimationPlayer::apply` 的浮点属性传入 `weights_mut`
+#[derive(Debug, Clone)]
+pub struct MorphTargets {
+    vertex_count: usize,
+    targets: Vec<MorphTarget>,
+    weights: Vec<Number>,
+}
+
+impl MorphTargets {
+    pub fn new(vertex_count: usize) -> Self {
+        Self { vertex_count, targets: Vec::new(), weights: Vec::new() }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// 添加变形目标, 权重为 0, 顶点数不符时返回 None
+    pub fn add_target(&mut self, target: MorphTarget) -> Option<usize> {
+        if target.vertex_count() != self.vertex_count {
+            return None;
+        }
+        self.targets.push(target);
+        self.weights.push(0.);
+        Some(self.targets.len() - 1)
+    }
+
+    pub fn targets(&self) -> &[MorphTarget] {
+        &self.targets
+    }
+
+    pub fn find_target(&self, name: &str) -> Option<usize> {
+        self.targets.iter().position(|target| target.name == name)
+    }
+
+    pub fn weights(&self) -> &[Number] {
+        &self.weights
+    }
+
+    pub fn weights_mut(&mut self) -> &mut [Number] {
+        &mut self.weights
+    }
+
+    pub fn set_weight(&mut self, index: usize, weight: Number) -> bool {
+        match self.weights.get_mut(index) {
+            Some(value) => {
+                *value = weight;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// 按权重绝对值从大到小选取至多 max_count 个生效目标, 用于上传 GPU
+    /// * 权重绝对值不大于 threshold 的目标忽略
+    pub fn active_targets(&self, max_count: usize, threshold: Number, indices: &mut Vec<usize>, weights: &mut Vec<Number>) {
+        indices.clear();
+        indices.extend((0..self.weights.len()).filter(|index| self.weights[*index].abs() > threshold));
+        indices.sort_by(|a, b| self.weights[*b].abs().total_cmp(&self.weights[*a].abs()));
+        indices.truncate(max_count);
+        weights.clear();
+        weights.extend(indices.iter().map(|index| self.weights[*index]));
+    }
+
+    /// 混合顶点位置, 基础数据数量不符时返回 false
+    pub fn blend_positions(&self, base: &[Vector3], result: &mut Vec<Vector3>) -> bool {
+        self.blend(base, |target| Some(target.positions()), result)
+    }
+
+    /// 混合法线并归一化, 无增量的目标不参与
+    pub fn blend_normals(&self, base: &[Vector3], result: &mut Vec<Vector3>) -> bool {
+        if !self.blend(base, |target| target.normals(), result) {
+            return false;
+        }
+        result.iter_mut().for_each(|normal| { normal.try_normalize_mut(Number::EPSILON); });
+        true
+    }
+
+    /// 混合切线并归一化, w 为副切线方向, 保持不变
+    pub fn blend_tangents(&self, base: &[Vector4], result: &mut Vec<Vector4>) -> bool {
+        if base.len() != self.vertex_count {
+            return false;
+        }
+        let xyz: Vec<Vector3> = base.iter().map(|tangent| tangent.xyz()).collect();
+        let mut blended = Vec::with_capacity(xyz.len());
+        self.blend(&xyz, |target| target.tangents(), &mut blended);
+        result.clear();
+        result.extend(blended.iter().zip(base.iter()).map(|(tangent, base)| {
+            let tangent = tangent.try_normalize(Number::EPSILON).unwrap_or(*tangent);
+            tangent.push(base.w)
+        }));
+        true
+    }
+
+    fn blend<'a>(&'a self, base: &[Vector3], deltas: impl Fn(&'a MorphTarget) -> Option<&'a [Vector3]>, result: &mut Vec<Vector3>) -> bool {
+        if base.len() != self.vertex_count {
+            return false;
+        }
+        result.clear();
+        result.extend_from_slice(base);
+        for (target, weight) in self.targets.iter().zip(self.weights.iter()) {
+            if *weight == 0. {
+                continue;
+            }
+            if let Some(deltas) = deltas(target) {
+                result.iter_mut().zip(deltas.iter()).for_each(|(value, delta)| *value += delta * *weight);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Vector4, animation::{AnimationPlayer, AnimationClip, AnimationChannel, EAnimationCurve, EAnimationLoop}, curve::{FrameCurve, KeyFrame}};
+
+    use super::{MorphTargets, MorphTarget};
+
+    fn morph() -> MorphTargets {
+        let mut morph = MorphTargets::new(2);
+        let mut smile = MorphTarget::new("smile", vec![Vector3::new(1., 0., 0.), Vector3::zeros()]);
+        assert!(smile.set_normals(Some(vec![Vector3::new(1., -1., 0.), Vector3::zeros()])));
+        assert!(!smile.set_tangents(Some(vec![Vector3::zeros()])));
+        assert_eq!(morph.add_target(smile), Some(0));
+        assert_eq!(morph.add_target(MorphTarget::new("blink", vec![Vector3::zeros(), Vector3::new(0., -2., 0.)])), Some(1));
+        assert_eq!(morph.add_target(MorphTarget::new("bad", vec![Vector3::zeros()])), None);
+        morph
+    }
+
+    #[test]
+    fn test_morph_blend() {
+        let mut morph = morph();
+        let base = [Vector3::new(0., 0., 0.), Vector3::new(0., 1., 0.)];
+        let normals = [Vector3::new(0., 1., 0.), Vector3::new(0., 0., 1.)];
+        morph.set_weight(0, 0.5);
+        morph.set_weight(1, 0.25);
+
+        let mut result = vec![];
+        assert!(morph.blend_positions(&base, &mut result));
+        assert_relative_eq!(result[0], Vector3::new(0.5, 0., 0.));
+        assert_relative_eq!(result[1], Vector3::new(0., 0.5, 0.));
+        assert!(!morph.blend_positions(&base[..1], &mut result));
+
+        assert!(morph.blend_normals(&normals, &mut result));
+        assert_relative_eq!(result[0], Vector3::new(1., 1., 0.).normalize(), epsilon = 0.0001);
+        assert_relative_eq!(result[1], Vector3::new(0., 0., 1.));
+
+        let tangents = [Vector4::new(2., 0., 0., -1.), Vector4::new(0., 0., 1., 1.)];
+        let mut result = vec![];
+        assert!(morph.blend_tangents(&tangents, &mut result));
+        assert_relative_eq!(result[0], Vector4::new(1., 0., 0., -1.));
+
+        // 选取权重最大的生效目标
+        morph.set_weight(0, 0.1);
+        morph.set_weight(1, -0.8);
+        let (mut indices, mut weights) = (vec![], vec![]);
+        morph.active_targets(1, 0.01, &mut indices, &mut weights);
+        assert_eq!((indices.as_slice(), weights.as_slice()), (&[1][..], &[-0.8][..]));
+        morph.set_weight(1, 0.);
+        morph.active_targets(4, 0.01, &mut indices, &mut weights);
+        assert_eq!(indices, vec![0]);
+    }
+
+    #[test]
+    fn test_morph_animation() {
+        let mut morph = morph();
+        let mut curve = FrameCurve::new();
+        curve.add_key(KeyFrame::linear(0., 0.));
+        curve.add_key(KeyFrame::linear(1., 1.));
+        let mut clip = AnimationClip::new("blink");
+        clip.add_channel(AnimationChannel::new(morph.find_target("blink").unwrap(), EAnimationCurve::Float(curve)));
+
+        let mut player = AnimationPlayer::new();
+        let clip = player.add_clip(clip);
+        player.play(0, clip, EAnimationLoop::Once);
+        player.update(0.5);
+        player.apply(&mut [], morph.weights_mut());
+        assert_relative_eq!(morph.weights()[1], 0.5);
+        assert_relative_eq!(morph.weights()[0], 0.);
+    }
+}
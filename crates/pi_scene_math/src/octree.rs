@@ -0,0 +1,442 @@
+use crate::{Number, Vector3, bounding_box::BoundingBox, frustum::{FrustumPlanes, EContainment}, ray::Ray, transform::Transform3};
+
+/// 八叉树条目句柄 - 数组下标 + 代数, 条目删除后旧句柄失效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OctreeId {
+    index: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct OctreeEntry<T> {
+    value: Option<T>,
+    minimum: Vector3,
+    maximum: Vector3,
+    node: u32,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct OctreeNode {
+    center: Vector3,
+    /// 节点单元半尺寸
+    half: Vector3,
+    depth: usize,
+    /// 8 个子节点连续存放, 此为第一个的下标
+    children: Option<u32>,
+    entries: Vec<u32>,
+}
+
+/// 八叉树 - 按世界空间轴对齐包围盒存放条目, 同 BabylonJS Octree
+/// * 节点条目数超过 capacity 且未达 max_depth 时分裂, 条目下放到能完全容纳它的子节点, 否则留在当前节点
+/// * looseness 为松散系数: 节点的容纳范围为单元尺寸的 looseness 倍 (中心不变), 1 为普通八叉树, 2 为常用的松散八叉树
+/// * 超出根节点范围的条目存放于根节点
+/// * 查询结果写入 result 前先清空
+#[derive(Debug, Clone)]
+pub struct Octree<T> {
+    capacity: usize,
+    max_depth: usize,
+    looseness: Number,
+    nodes: Vec<OctreeNode>,
+    entries: Vec<OctreeEntry<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Octree<T> {
+    pub fn new(minimum: &Vector3, maximum: &Vector3, capacity: usize, max_depth: usize) -> Self {
+        Self::new_loose(minimum, maximum, capacity, max_depth, 1.)
+    }
+
+    /// 松散八叉树, looseness 限制为不小于 1
+    pub fn new_loose(minimum: &Vector3, maximum: &Vector3, capacity: usize, max_depth: usize, looseness: Number) -> Self {
+        let root = OctreeNode {
+            center: (minimum + maximum) * 0.5,
+            half: (maximum - minimum) * 0.5,
+            depth: 0,
+            children: None,
+            entries: Vec::new(),
+        };
+        Self { capacity: capacity.max(1), max_depth, looseness: looseness.max(1.), nodes: vec![root], entries: Vec::new(), free: Vec::new() }
+    }
+
+    pub fn looseness(&self) -> Number {
+        self.looseness
+    }
+
+    /// 条目数量
+    pub fn len(&self) -> usize {
+        self.entries.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 节点数量
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn get(&self, id: OctreeId) -> Option<&T> {
+        self.entry(id).and_then(|entry| entry.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: OctreeId) -> Option<&mut T> {
+        self.entries.get_mut(id.index as usize)
+            .filter(|entry| entry.generation == id.generation)
+            .and_then(|entry| entry.value.as_mut())
+    }
+
+    /// 条目世界空间包围盒 (minimum, maximum)
+    pub fn bounds(&self, id: OctreeId) -> Option<(Vector3, Vector3)> {
+        self.entry(id).map(|entry| (entry.minimum, entry.maximum))
+    }
+
+    /// 添加条目
+    pub fn insert(&mut self, value: T, minimum: &Vector3, maximum: &Vector3) -> OctreeId {
+        let entry = OctreeEntry { value: Some(value), minimum: *minimum, maximum: *maximum, node: 0, generation: 0 };
+        let id = match self.free.pop() {
+            Some(index) => {
+                let generation = self.entries[index as usize].generation + 1;
+                self.entries[index as usize] = OctreeEntry { generation, ..entry };
+                OctreeId { index, generation }
+            },
+            None => {
+                self.entries.push(entry);
+                OctreeId { index: (self.entries.len() - 1) as u32, generation: 0 }
+            },
+        };
+        self.place(0, id.index);
+        id
+    }
+
+    /// 删除条目, 返回其值
+    pub fn remove(&mut self, id: OctreeId) -> Option<T> {
+        self.entry(id)?;
+        self.detach(id.index);
+        self.free.push(id.index);
+        self.entries[id.index as usize].value.take()
+    }
+
+    /// 条目移动后更新包围盒
+    /// * 仍在所在节点容纳范围内且不能下放到子节点时只更新数据, 否则重新插入
+    pub fn update(&mut self, id: OctreeId, minimum: &Vector3, maximum: &Vector3) -> bool {
+        if self.entry(id).is_none() {
+            return false;
+        }
+        let entry = &mut self.entries[id.index as usize];
+        entry.minimum = *minimum;
+        entry.maximum = *maximum;
+
+        let node = entry.node as usize;
+        let stays = (node == 0 || self.node_contains(node, minimum, maximum))
+            && (self.nodes[node].children.is_none() || self.fitting_child(node, minimum, maximum).is_none());
+        if !stays {
+            self.detach(id.index);
+            self.place(0, id.index);
+        }
+        true
+    }
+
+    /// 以变换后的局部包围盒更新条目, transform 需已 `calc_matrix`
+    pub fn update_transform(&mut self, id: OctreeId, local_minimum: &Vector3, local_maximum: &Vector3, transform: &Transform3) -> bool {
+        let bounding = BoundingBox::new(local_minimum, local_maximum, Some(transform.matrix()));
+        self.update(id, &bounding.minimum_world, &bounding.maximum_world)
+    }
+
+    /// 与视锥相交或在视锥内的条目
+    /// * 节点完全在视锥内时其子树条目不再逐个测试
+    pub fn query_frustum(&self, frustum: &FrustumPlanes, result: &mut Vec<OctreeId>) {
+        result.clear();
+        let mut stack = vec![(0usize, false)];
+        while let Some((node, inside)) = stack.pop() {
+            // 根节点可能存放超出范围的条目, 不做节点测试
+            let inside = inside || (node != 0 && {
+                let (minimum, maximum) = self.loose_bounds(node);
+                match frustum.intersects_aabb(&minimum, &maximum) {
+                    EContainment::Outside => continue,
+                    EContainment::Inside => true,
+                    EContainment::Intersecting => false,
+                }
+            });
+            for index in self.nodes[node].entries.iter() {
+                let entry = &self.entries[*index as usize];
+                if inside || frustum.intersects_aabb(&entry.minimum, &entry.maximum) != EContainment::Outside {
+                    result.push(self.id_of(*index));
+                }
+            }
+            self.push_children(node, inside, &mut stack);
+        }
+    }
+
+    /// 与球相交的条目
+    pub fn query_sphere(&self, center: &Vector3, radius: Number, result: &mut Vec<OctreeId>) {
+        self.query(
+            |minimum, maximum| BoundingBox::intersects_sphere_min_max(minimum, maximum, center, radius),
+            result,
+        );
+    }
+
+    /// 与轴对齐包围盒相交的条目
+    pub fn query_box(&self, minimum: &Vector3, maximum: &Vector3, result: &mut Vec<OctreeId>) {
+        self.query(
+            |other_minimum, other_maximum| BoundingBox::intersects_min_max_min_max(minimum, maximum, other_minimum, other_maximum),
+            result,
+        );
+    }
+
+    /// 包围盒与射线相交的条目及其距离, 按距离从近到远排序
+    pub fn query_ray(&self, ray: &Ray, result: &mut Vec<(OctreeId, Number)>) {
+        result.clear();
+        let mut stack = vec![0usize];
+        while let Some(node) = stack.pop() {
+            let (minimum, maximum) = self.loose_bounds(node);
+            if node != 0 && ray.intersects_aabb(&minimum, &maximum).is_none() {
+                continue;
+            }
+            for index in self.nodes[node].entries.iter() {
+                let entry = &self.entries[*index as usize];
+                if let Some(distance) = ray.intersects_aabb(&entry.minimum, &entry.maximum) {
+                    result.push((self.id_of(*index), distance));
+                }
+            }
+            if let Some(first) = self.nodes[node].children {
+                stack.extend((0..8).map(|child| (first + child) as usize));
+            }
+        }
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+    }
+
+    fn query(&self, intersects: impl Fn(&Vector3, &Vector3) -> bool, result: &mut Vec<OctreeId>) {
+        result.clear();
+        let mut stack = vec![0usize];
+        while let Some(node) = stack.pop() {
+            let (minimum, maximum) = self.loose_bounds(node);
+            // 根节点可能存放超出范围的条目
+            if node != 0 && !intersects(&minimum, &maximum) {
+                continue;
+            }
+            for index in self.nodes[node].entries.iter() {
+                let entry = &self.entries[*index as usize];
+                if intersects(&entry.minimum, &entry.maximum) {
+                    result.push(self.id_of(*index));
+                }
+            }
+            if let Some(first) = self.nodes[node].children {
+                stack.extend((0..8).map(|child| (first + child) as usize));
+            }
+        }
+    }
+
+    fn push_children(&self, node: usize, inside: bool, stack: &mut Vec<(usize, bool)>) {
+        if let Some(first) = self.nodes[node].children {
+            stack.extend((0..8).map(|child| ((first + child) as usize, inside)));
+        }
+    }
+
+    fn entry(&self, id: OctreeId) -> Option<&OctreeEntry<T>> {
+        self.entries.get(id.index as usize).filter(|entry| entry.generation == id.generation && entry.value.is_some())
+    }
+
+    fn id_of(&self, index: u32) -> OctreeId {
+        OctreeId { index, generation: self.entries[index as usize].generation }
+    }
+
+    /// 节点容纳范围
+    fn loose_bounds(&self, node: usize) -> (Vector3, Vector3) {
+        let node = &self.nodes[node];
+        let half = node.half * self.looseness;
+        (node.center - half, node.center + half)
+    }
+
+    fn node_contains(&self, node: usize, minimum: &Vector3, maximum: &Vector3) -> bool {
+        let (node_minimum, node_maximum) = self.loose_bounds(node);
+        (0..3).all(|i| node_minimum[i] <= minimum[i] && maximum[i] <= node_maximum[i])
+    }
+
+    /// 包围盒中心所在卦限的子节点, 其容纳范围能完全容纳包围盒时返回子节点下标
+    fn fitting_child(&self, node: usize, minimum: &Vector3, maximum: &Vector3) -> Option<usize> {
+        let first = self.nodes[node].children? as usize;
+        let center = (minimum + maximum) * 0.5;
+        let node_center = self.nodes[node].center;
+        let octant = (0..3).fold(0, |octant, i| if center[i] >= node_center[i] { octant | (1 << i) } else { octant });
+        let child = first + octant;
+        if self.node_contains(child, minimum, maximum) {
+            Some(child)
+        } else {
+            None
+        }
+    }
+
+    /// 自 node 向下放置条目, 必要时分裂
+    fn place(&mut self, mut node: usize, index: u32) {
+        let (minimum, maximum) = {
+            let entry = &self.entries[index as usize];
+            (entry.minimum, entry.maximum)
+        };
+        while let Some(child) = self.fitting_child(node, &minimum, &maximum) {
+            node = child;
+        }
+        self.nodes[node].entries.push(index);
+        self.entries[index as usize].node = node as u32;
+
+        let target = &self.nodes[node];
+        if target.children.is_none() && target.entries.len() > self.capacity && target.depth < self.max_depth {
+            self.split(node);
+        }
+    }
+
+    fn split(&mut self, node: usize) {
+        let first = self.nodes.len() as u32;
+        let (center, half, depth) = (self.nodes[node].center, self.nodes[node].half * 0.5, self.nodes[node].depth + 1);
+        for octant in 0..8 {
+            let offset = Vector3::from_fn(|i, _| if octant & (1 << i) != 0 { half[i] } else { -half[i] });
+            self.nodes.push(OctreeNode { center: center + offset, half, depth, children: None, entries: Vec::new() });
+        }
+        self.nodes[node].children = Some(first);
+
+        let entries = std::mem::take(&mut self.nodes[node].entries);
+        for index in entries {
+            self.place(node, index);
+        }
+    }
+
+    fn detach(&mut self, index: u32) {
+        let node = self.entries[index as usize].node as usize;
+        let entries = &mut self.nodes[node].entries;
+        if let Some(position) = entries.iter().position(|other| *other == index) {
+            entries.swap_remove(position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Matrix, Isometry3, Point3, coordiante_system::CoordinateSytem3, camera::{TPerspectiveCameraTool, EDepthRange}, frustum::{FrustumPlanes, EContainment}, ray::Ray, transform::Transform3};
+
+    use super::{Octree, OctreeId};
+
+    fn grid(octree: &mut Octree<usize>) -> Vec<(OctreeId, Vector3, Vector3)> {
+        let mut items = vec![];
+        for i in 0..1000 {
+            let center = Vector3::new((i % 10) as Number * 10. - 45., ((i / 10) % 10) as Number * 10. - 45., (i / 100) as Number * 10. - 45.);
+            let extent = Vector3::repeat(1. + (i % 7) as Number);
+            let (minimum, maximum) = (center - extent, center + extent);
+            items.push((octree.insert(i, &minimum, &maximum), minimum, maximum));
+        }
+        items
+    }
+
+    fn sorted(mut ids: Vec<OctreeId>, octree: &Octree<usize>) -> Vec<usize> {
+        let mut values: Vec<usize> = ids.drain(..).map(|id| *octree.get(id).unwrap()).collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_octree_queries() {
+        for looseness in [1., 2.] {
+            let mut octree = Octree::new_loose(&Vector3::repeat(-50.), &Vector3::repeat(50.), 8, 6, looseness);
+            let items = grid(&mut octree);
+            assert_eq!(octree.len(), 1000);
+            assert!(octree.node_count() > 1);
+
+            // 与逐个测试结果一致
+            let (minimum, maximum) = (Vector3::new(-20., -5., 0.), Vector3::new(12., 30., 8.));
+            let mut result = vec![];
+            octree.query_box(&minimum, &maximum, &mut result);
+            let expected: Vec<usize> = items.iter().enumerate()
+                .filter(|(_, (_, a, b))| (0..3).all(|i| a[i] <= maximum[i] && b[i] >= minimum[i]))
+                .map(|(i, _)| i).collect();
+            assert_eq!(sorted(result, &octree), expected);
+
+            let center = Vector3::new(5., 5., 5.);
+            let mut result = vec![];
+            octree.query_sphere(&center, 12., &mut result);
+            let expected: Vec<usize> = items.iter().enumerate()
+                .filter(|(_, (_, a, b))| (center - center.sup(a).inf(b)).norm() <= 12.)
+                .map(|(i, _)| i).collect();
+            assert_eq!(sorted(result, &octree), expected);
+
+            let view: Matrix = Isometry3::look_at_rh(&Point3::new(0., 0., 80.), &Point3::origin(), &Vector3::y()).to_homogeneous();
+            let projection = CoordinateSytem3::perspective_rh(0.5, 1., 1., 100., true, EDepthRange::ZeroToOne);
+            let mut frustum = FrustumPlanes::default();
+            frustum.from_transform_matrix(&(projection * view), EDepthRange::ZeroToOne);
+            let mut result = vec![];
+            octree.query_frustum(&frustum, &mut result);
+            let expected: Vec<usize> = items.iter().enumerate()
+                .filter(|(_, (_, a, b))| frustum.intersects_aabb(a, b) != EContainment::Outside)
+                .map(|(i, _)| i).collect();
+            assert!(!expected.is_empty() && expected.len() < 1000);
+            assert_eq!(sorted(result, &octree), expected);
+
+            let ray = Ray::new(Vector3::new(-100., -45., -45.), Vector3::x(), Number::MAX);
+            let mut hits = vec![];
+            octree.query_ray(&ray, &mut hits);
+            assert_eq!(hits.len(), 10);
+            assert_eq!(*octree.get(hits[0].0).unwrap(), 0);
+            assert_relative_eq!(hits[0].1, 54.);
+            assert!(hits.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        }
+    }
+
+    #[test]
+    fn test_octree_update() {
+        let mut octree = Octree::new(&Vector3::repeat(-50.), &Vector3::repeat(50.), 2, 4);
+        let items = grid(&mut octree);
+        let (id, minimum, maximum) = items[0];
+
+        // 通过变换移动到另一侧
+        let mut transform = Transform3::default();
+        transform.set_translation_from_floats(90., 90., 90.);
+        transform.calc_matrix();
+        assert!(octree.update_transform(id, &minimum, &maximum, &transform));
+        let (moved_minimum, _) = octree.bounds(id).unwrap();
+        assert_relative_eq!(moved_minimum, minimum + Vector3::repeat(90.));
+
+        let mut result = vec![];
+        octree.query_box(&minimum, &maximum, &mut result);
+        assert!(!result.contains(&id));
+        octree.query_sphere(&Vector3::repeat(45.), 0.5, &mut result);
+        assert!(result.contains(&id));
+
+        // 移出根节点范围仍可查询
+        assert!(octree.update(id, &Vector3::repeat(200.), &Vector3::repeat(201.)));
+        octree.query_box(&Vector3::repeat(199.), &Vector3::repeat(202.), &mut result);
+        assert_eq!(result, vec![id]);
+
+        // 删除后句柄失效, 槽位复用
+        assert_eq!(octree.remove(id), Some(0));
+        assert!(octree.get(id).is_none());
+        assert!(!octree.update(id, &minimum, &maximum));
+        let other = octree.insert(1000, &minimum, &maximum);
+        assert!(octree.get(id).is_none());
+        assert_eq!(octree.get(other), Some(&1000));
+        assert_eq!(octree.len(), 1000);
+    }
+
+    #[test]
+    fn test_octree_outside_root() {
+        let mut octree = Octree::new(&Vector3::repeat(-50.), &Vector3::repeat(50.), 2, 4);
+        grid(&mut octree);
+        // 超出根节点范围, 位于视锥内
+        let id = octree.insert(1000, &Vector3::new(0., 0., 60.), &Vector3::new(1., 1., 61.));
+
+        let view: Matrix = Isometry3::look_at_rh(&Point3::new(0., 0., 80.), &Point3::origin(), &Vector3::y()).to_homogeneous();
+        let projection = CoordinateSytem3::perspective_rh(0.5, 1., 1., 100., true, EDepthRange::ZeroToOne);
+        let mut frustum = FrustumPlanes::default();
+        frustum.from_transform_matrix(&(projection * view), EDepthRange::ZeroToOne);
+        let mut result = vec![];
+        octree.query_frustum(&frustum, &mut result);
+        assert!(result.contains(&id));
+
+        // 视锥只看到根节点范围外时, 根节点条目仍被测试
+        let view: Matrix = Isometry3::look_at_rh(&Point3::new(0., 0., 80.), &Point3::new(0., 0., 200.), &Vector3::y()).to_homogeneous();
+        frustum.from_transform_matrix(&(projection * view), EDepthRange::ZeroToOne);
+        let far = octree.insert(1001, &Vector3::new(0., 0., 100.), &Vector3::new(1., 1., 101.));
+        octree.query_frustum(&frustum, &mut result);
+        assert_eq!(result, vec![far]);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{Number, Vector2, Vector3, Isometry3, coordiante_system::CoordinateSytem3, vector::{TToolMatrix, TToolVector3}, camera::Camera};
+
+/// 环绕相机控制器 - 同 BabylonJS ArcRotateCamera
+/// * 以 target 为中心, alpha 为绕向上轴的角度, beta 为与向上轴的夹角, radius 为距离
+/// * 输入为抽象增量, 每帧调用 `update` 应用并按 inertia 衰减
+/// * inertia 为 0 时输入在下一次 `update` 立即生效
+#[derive(Debug, Clone)]
+pub struct OrbitController {
+    coord: CoordinateSytem3,
+    pub alpha: Number,
+    pub beta: Number,
+    pub radius: Number,
+    pub target: Vector3,
+    pub lower_alpha_limit: Option<Number>,
+    pub upper_alpha_limit: Option<Number>,
+    /// 默认略大于 0, 避免视线与向上轴共线
+    pub lower_beta_limit: Number,
+    /// 默认略小于 PI
+    pub upper_beta_limit: Number,
+    pub lower_radius_limit: Option<Number>,
+    pub upper_radius_limit: Option<Number>,
+    /// 惯性 - 每次 `update` 后剩余增量比例, [0, 1)
+    pub inertia: Number,
+    inertial_alpha_offset: Number,
+    inertial_beta_offset: Number,
+    inertial_radius_offset: Number,
+    /// 视平面内平移 x 右 y 上
+    inertial_panning: Vector2,
+    /// 缩放朝向的世界空间点
+    zoom_point: Option<Vector3>,
+}
+
+impl OrbitController {
+    pub fn new(coord: CoordinateSytem3, alpha: Number, beta: Number, radius: Number, target: Vector3) -> Self {
+        Self {
+            coord,
+            alpha,
+            beta,
+            radius,
+            target,
+            lower_alpha_limit: None,
+            upper_alpha_limit: None,
+            lower_beta_limit: 0.01,
+            upper_beta_limit: crate::consts::PI - 0.01,
+            lower_radius_limit: None,
+            upper_radius_limit: None,
+            inertia: 0.9,
+            inertial_alpha_offset: 0.,
+            inertial_beta_offset: 0.,
+            inertial_radius_offset: 0.,
+            inertial_panning: Vector2::zeros(),
+            zoom_point: None,
+        }
+    }
+
+    pub fn coordinate_system(&self) -> &CoordinateSytem3 {
+        &self.coord
+    }
+
+    /// 旋转增量 (弧度)
+    pub fn rotate(&mut self, delta_alpha: Number, delta_beta: Number) {
+        self.inertial_alpha_offset += delta_alpha;
+        self.inertial_beta_offset += delta_beta;
+    }
+
+    /// 视平面内平移增量 (世界单位), x 向右 y 向上
+    pub fn pan(&mut self, delta_x: Number, delta_y: Number) {
+        self.inertial_panning.x += delta_x;
+        self.inertial_panning.y += delta_y;
+    }
+
+    /// 缩放增量 - 正值拉近 (radius 减小)
+    pub fn zoom(&mut self, delta: Number) {
+        self.inertial_radius_offset += delta;
+        self.zoom_point = None;
+    }
+
+    /// 朝向光标所指世界空间点缩放, 该点在屏幕上的位置保持不变
+    pub fn zoom_to_cursor(&mut self, delta: Number, point: &Vector3) {
+        self.inertial_radius_offset += delta;
+        self.zoom_point = Some(*point);
+    }
+
+    /// 清除所有未应用的增量
+    pub fn stop(&mut self) {
+        self.inertial_alpha_offset = 0.;
+        self.inertial_beta_offset = 0.;
+        self.inertial_radius_offset = 0.;
+        self.inertial_panning.fill(0.);
+        self.zoom_point = None;
+    }
+
+    /// 应用增量 衰减惯性 并限制范围
+    pub fn update(&mut self) {
+        if self.inertial_alpha_offset != 0. || self.inertial_beta_offset != 0. {
+            self.alpha += self.inertial_alpha_offset;
+            self.beta += self.inertial_beta_offset;
+            self.inertial_alpha_offset = Self::decay(self.inertial_alpha_offset, self.inertia);
+            self.inertial_beta_offset = Self::decay(self.inertial_beta_offset, self.inertia);
+        }
+
+        if self.inertial_panning.x != 0. || self.inertial_panning.y != 0. {
+            let (right, up) = self.view_plane_axes();
+            self.target += right * self.inertial_panning.x + up * self.inertial_panning.y;
+            self.inertial_panning.x = Self::decay(self.inertial_panning.x, self.inertia);
+            self.inertial_panning.y = Self::decay(self.inertial_panning.y, self.inertia);
+        }
+
+        if self.inertial_radius_offset != 0. {
+            let old_radius = self.radius;
+            self.radius -= self.inertial_radius_offset;
+            self.clamp_radius();
+            if let Some(point) = self.zoom_point {
+                // 相机沿视线到目标点的连线缩放, 目标点随之按相同比例靠近
+                self.target += (point - self.target) * (1. - self.radius / old_radius);
+            }
+            self.inertial_radius_offset = Self::decay(self.inertial_radius_offset, self.inertia);
+            if self.inertial_radius_offset == 0. {
+                self.zoom_point = None;
+            }
+        }
+
+        self.check_limits();
+    }
+
+    /// 限制 alpha beta radius 范围
+    pub fn check_limits(&mut self) {
+        if let Some(lower) = self.lower_alpha_limit {
+            self.alpha = self.alpha.max(lower);
+        }
+        if let Some(upper) = self.upper_alpha_limit {
+            self.alpha = self.alpha.min(upper);
+        }
+        // 上下限可能被设为相反或 NaN, 不用 clamp 以免 panic
+        self.beta = self.beta.max(self.lower_beta_limit).min(self.upper_beta_limit);
+        self.clamp_radius();
+    }
+
+    /// 相机世界空间位置
+    pub fn position(&self) -> Vector3 {
+        let (sin_alpha, cos_alpha) = self.alpha.sin_cos();
+        let (sin_beta, cos_beta) = self.beta.sin_cos();
+        // 参考坐标系 (左手 Y 向上) 下的偏移, 转换到控制器坐标系
+        let offset = Vector3::new(cos_alpha * sin_beta, cos_beta, sin_alpha * sin_beta) * self.radius;
+        self.target + self.coord.basis().transpose() * offset
+    }
+
+    /// 视图变换
+    pub fn view(&self, result: &mut Isometry3) {
+        self.coord.lookat(&self.position(), &self.target, &self.coord.up(), result);
+    }
+
+    /// 设置相机视图
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.lookat(&self.position(), &self.target, &self.coord.up());
+    }
+
+    /// 视平面向右 向上的世界空间方向
+    fn view_plane_axes(&self) -> (Vector3, Vector3) {
+        let mut view = Isometry3::identity();
+        self.view(&mut view);
+        let rotation = view.rotation.inverse();
+        (rotation * Vector3::x(), rotation * Vector3::y())
+    }
+
+    fn clamp_radius(&mut self) {
+        if let Some(lower) = self.lower_radius_limit {
+            self.radius = self.radius.max(lower);
+        }
+        if let Some(upper) = self.upper_radius_limit {
+            self.radius = self.radius.min(upper);
+        }
+        self.radius = self.radius.max(Number::EPSILON);
+    }
+
+    fn decay(offset: Number, inertia: Number) -> Number {
+        let offset = offset * inertia;
+        if offset.abs() < Number::EPSILON {
+            0.
+        } else {
+            offset
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, Isometry3, coordiante_system::{CoordinateSytem3, ECoordinateSytem3, EUpAxis}, consts::FRAC_PI_2};
+
+    use super::OrbitController;
+
+    #[test]
+    fn test_orbit_position() {
+        let mut orbit = OrbitController::new(CoordinateSytem3::left(), 0., FRAC_PI_2, 10., Vector3::new(0., 1., 0.));
+        assert_relative_eq!(orbit.position(), Vector3::new(10., 1., 0.), epsilon = 0.0001);
+
+        orbit.inertia = 0.;
+        orbit.rotate(FRAC_PI_2, 0.);
+        orbit.update();
+        assert_relative_eq!(orbit.position(), Vector3::new(0., 1., 10.), epsilon = 0.0001);
+
+        // beta 限制避免越过头顶
+        orbit.rotate(0., -10.);
+        orbit.update();
+        assert_relative_eq!(orbit.beta, orbit.lower_beta_limit);
+
+        // Z 向上坐标系, beta 0 位于目标正上方
+        let blender = CoordinateSytem3::new(ECoordinateSytem3::Right, EUpAxis::Z);
+        let mut orbit = OrbitController::new(blender, 0., FRAC_PI_2, 10., Vector3::zeros());
+        orbit.lower_beta_limit = 0.;
+        orbit.beta = 0.;
+        assert_relative_eq!(orbit.position(), Vector3::new(0., 0., 10.), epsilon = 0.0001);
+
+        // 视图将目标变换到视线正前方
+        orbit.beta = 1.;
+        let mut view = Isometry3::identity();
+        orbit.view(&mut view);
+        let target = view * nalgebra::Point3::from(orbit.target);
+        assert_relative_eq!(target.coords, Vector3::new(0., 0., -10.), epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_orbit_inertia_and_limits() {
+        let mut orbit = OrbitController::new(CoordinateSytem3::left(), 0., FRAC_PI_2, 10., Vector3::zeros());
+        orbit.inertia = 0.5;
+        orbit.rotate(1., 0.);
+        for _ in 0..100 {
+            orbit.update();
+        }
+        assert_relative_eq!(orbit.alpha, 2., epsilon = 0.0001);
+
+        orbit.lower_radius_limit = Some(2.);
+        orbit.upper_radius_limit = Some(20.);
+        orbit.zoom(100.);
+        orbit.update();
+        assert_relative_eq!(orbit.radius, 2.);
+        orbit.stop();
+        orbit.zoom(-100.);
+        orbit.update();
+        assert_relative_eq!(orbit.radius, 20.);
+
+        // 上下限相反或为 NaN 时不 panic
+        orbit.lower_beta_limit = 2.;
+        orbit.upper_beta_limit = 1.;
+        orbit.check_limits();
+        assert_relative_eq!(orbit.beta, 1.);
+        orbit.lower_beta_limit = Number::NAN;
+        orbit.upper_beta_limit = Number::NAN;
+        orbit.check_limits();
+        assert_relative_eq!(orbit.beta, 1.);
+    }
+
+    #[test]
+    fn test_orbit_pan_and_zoom_to_cursor() {
+        let mut orbit = OrbitController::new(CoordinateSytem3::right(), FRAC_PI_2, FRAC_PI_2, 10., Vector3::zeros());
+        orbit.inertia = 0.;
+        // 右手系 alpha = PI/2 时相机位于 -Z, 看向 +Z, 向右为 -X
+        assert_relative_eq!(orbit.position(), Vector3::new(0., 0., -10.), epsilon = 0.0001);
+        orbit.pan(1., 2.);
+        orbit.update();
+        assert_relative_eq!(orbit.target, Vector3::new(-1., 2., 0.), epsilon = 0.0001);
+        assert_relative_eq!(orbit.position(), Vector3::new(-1., 2., -10.), epsilon = 0.0001);
+
+        // 朝光标点缩放: 相机 目标点 光标点 的相对关系保持, 光标点位置不变
+        let point = Vector3::new(3., 2., 0.);
+        let direction: Vector3 = (point - orbit.position()).normalize();
+        orbit.zoom_to_cursor(5., &point);
+        orbit.update();
+        assert_relative_eq!(orbit.radius, 5.);
+        assert_relative_eq!(orbit.target, Vector3::new(1., 2., 0.), epsilon = 0.0001);
+        let after: Vector3 = (point - orbit.position()).normalize();
+        assert_relative_eq!(after, direction, epsilon = 0.0001);
+    }
+}
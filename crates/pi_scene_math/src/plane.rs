@@ -1,6 +1,18 @@
-use crate::{Number, Vector3};
+use crate::{Number, Vector3, Matrix, coordiante_system::CoordinateSytem3, vector::TToolMatrix};
 
-#[derive(Debug)]
+/// 点相对平面的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EPlaneSide {
+    /// 法线指向的一侧
+    Front,
+    /// 法线反方向的一侧
+    Back,
+    /// 在平面上
+    On,
+}
+
+/// 平面 `normal · p + d = 0`
+#[derive(Debug, Clone, Copy)]
 pub struct Plane {
     pub normal: Vector3,
     pub d: Number,
@@ -13,8 +25,40 @@ impl Default for Plane {
 }
 
 impl Plane {
-    pub fn from_points(&mut self, _p1: &Vector3, _p2: &Vector3, _p3: &Vector3) {}
-    pub fn from_point_and_normal(&mut self, _origin: &Vector3, _normal: &Vector3) {}
+    pub fn new(a: Number, b: Number, c: Number, d: Number) -> Self {
+        Self { normal: Vector3::new(a, b, c), d }
+    }
+
+    /// 三点确定平面, 法线方向为 (p2 - p1) x (p3 - p1)
+    pub fn from_points(&mut self, p1: &Vector3, p2: &Vector3, p3: &Vector3) {
+        let x1 = p2.x - p1.x;
+        let y1 = p2.y - p1.y;
+        let z1 = p2.z - p1.z;
+        let x2 = p3.x - p1.x;
+        let y2 = p3.y - p1.y;
+        let z2 = p3.z - p1.z;
+        let yz = y1 * z2 - z1 * y2;
+        let xz = z1 * x2 - x1 * z2;
+        let xy = x1 * y2 - y1 * x2;
+        let pyth = (yz * yz + xz * xz + xy * xy).sqrt();
+        let inv_pyth = if pyth != 0. { 1.0 / pyth } else { 0. };
+
+        self.normal.x = yz * inv_pyth;
+        self.normal.y = xz * inv_pyth;
+        self.normal.z = xy * inv_pyth;
+        self.d = -(self.normal.x * p1.x + self.normal.y * p1.y + self.normal.z * p1.z);
+    }
+
+    /// 过一点且法线为 normal 的平面, normal 会被单位化
+    pub fn from_point_and_normal(&mut self, origin: &Vector3, normal: &Vector3) {
+        let norm = normal.norm();
+        if norm > 0. {
+            self.normal.copy_from(&(normal / norm));
+        } else {
+            self.normal.fill(0.);
+        }
+        self.d = -self.normal.dot(origin);
+    }
 
     pub fn normalize(&mut self) {
         let norm = ((self.normal[0] * self.normal[0])
@@ -33,12 +77,133 @@ impl Plane {
     }
 
     pub fn dot_coordinate(&self, x: Number, y: Number, z: Number) -> Number {
-        return (((self.normal[0] * x) + (self.normal[1] * y))
+        (((self.normal[0] * x) + (self.normal[1] * y))
             + (self.normal[2] * z))
-            + self.d;
+            + self.d
     }
     pub fn dot_coordinate2(&self, center: &Vector3) -> Number {
-        return self.normal.dot(center)
-            + self.d;
+        self.normal.dot(center)
+            + self.d
+    }
+
+    /// 点到平面的有符号距离, 要求平面已单位化
+    pub fn signed_distance_to(&self, point: &Vector3) -> Number {
+        self.dot_coordinate2(point)
+    }
+
+    /// 点在平面上的投影, 要求平面已单位化
+    pub fn project_point(&self, point: &Vector3, result: &mut Vector3) {
+        let distance = self.signed_distance_to(point);
+        result.copy_from(&(point - self.normal * distance));
+    }
+
+    /// 点关于平面的镜像, 要求平面已单位化
+    pub fn reflect_point(&self, point: &Vector3, result: &mut Vector3) {
+        let distance = self.signed_distance_to(point);
+        result.copy_from(&(point - self.normal * (2. * distance)));
+    }
+
+    /// 方向关于平面的镜像 (不受 d 影响), 要求平面已单位化
+    pub fn reflect_direction(&self, direction: &Vector3, result: &mut Vector3) {
+        let dot = self.normal.dot(direction);
+        result.copy_from(&(direction - self.normal * (2. * dot)));
+    }
+
+    /// 平面经矩阵变换后的平面 - 使用逆矩阵的转置变换平面方程
+    /// * 矩阵不可逆时返回 false, result 不变
+    pub fn transform(&self, transformation: &Matrix, result: &mut Plane) -> bool {
+        let mut inverse = *transformation;
+        if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+            return false;
+        }
+
+        let m = inverse.as_slice();
+        let x = self.normal.x; let y = self.normal.y; let z = self.normal.z; let d = self.d;
+
+        result.normal.x = x * m[0] + y * m[1] + z * m[2] + d * m[3];
+        result.normal.y = x * m[4] + y * m[5] + z * m[6] + d * m[7];
+        result.normal.z = x * m[8] + y * m[9] + z * m[10] + d * m[11];
+        result.d = x * m[12] + y * m[13] + z * m[14] + d * m[15];
+        result.normalize();
+        true
+    }
+
+    /// 点相对平面的位置, 距离绝对值不超过 epsilon 视为在平面上
+    pub fn classify_point(&self, point: &Vector3, epsilon: Number) -> EPlaneSide {
+        let distance = self.signed_distance_to(point);
+        if distance > epsilon {
+            EPlaneSide::Front
+        } else if distance < -epsilon {
+            EPlaneSide::Back
+        } else {
+            EPlaneSide::On
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Matrix, Rotation3};
+
+    use super::{Plane, EPlaneSide};
+
+    #[test]
+    fn test_plane_build() {
+        let mut plane = Plane::default();
+        plane.from_points(&Vector3::new(0., 2., 0.), &Vector3::new(0., 2., 1.), &Vector3::new(1., 2., 0.));
+        assert_relative_eq!(plane.normal, Vector3::new(0., 1., 0.), epsilon = 0.00001);
+        assert_relative_eq!(plane.d, -2., epsilon = 0.00001);
+
+        let mut plane2 = Plane::default();
+        plane2.from_point_and_normal(&Vector3::new(0., 2., 0.), &Vector3::new(0., 3., 0.));
+        assert_relative_eq!(plane2.normal, plane.normal, epsilon = 0.00001);
+        assert_relative_eq!(plane2.d, plane.d, epsilon = 0.00001);
+
+        assert_relative_eq!(plane.signed_distance_to(&Vector3::new(5., 5., 5.)), 3., epsilon = 0.00001);
+        assert_eq!(plane.classify_point(&Vector3::new(5., 5., 5.), 0.0001), EPlaneSide::Front);
+        assert_eq!(plane.classify_point(&Vector3::new(5., -5., 5.), 0.0001), EPlaneSide::Back);
+        assert_eq!(plane.classify_point(&Vector3::new(5., 2., 5.), 0.0001), EPlaneSide::On);
+    }
+
+    #[test]
+    fn test_plane_project_reflect() {
+        let mut plane = Plane::default();
+        plane.from_point_and_normal(&Vector3::new(1., 1., 1.), &Vector3::new(1., 0., 0.));
+
+        let mut result = Vector3::zeros();
+        plane.project_point(&Vector3::new(4., 2., 3.), &mut result);
+        assert_relative_eq!(result, Vector3::new(1., 2., 3.), epsilon = 0.00001);
+
+        plane.reflect_point(&Vector3::new(4., 2., 3.), &mut result);
+        assert_relative_eq!(result, Vector3::new(-2., 2., 3.), epsilon = 0.00001);
+
+        plane.reflect_direction(&Vector3::new(1., 1., 0.), &mut result);
+        assert_relative_eq!(result, Vector3::new(-1., 1., 0.), epsilon = 0.00001);
+    }
+
+    #[test]
+    fn test_plane_transform() {
+        let mut plane = Plane::default();
+        let point = Vector3::new(0., 2., 0.);
+        plane.from_point_and_normal(&point, &Vector3::new(0., 1., 0.));
+
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), 0.7);
+        let mut transformation = rotation.to_homogeneous();
+        transformation.append_nonuniform_scaling_mut(&Vector3::new(2., 3., 4.));
+        transformation.append_translation_mut(&Vector3::new(10., -5., 3.));
+
+        let mut result = Plane::default();
+        assert!(plane.transform(&transformation, &mut result));
+
+        let moved = transformation.transform_point(&point.into()).coords;
+        assert_relative_eq!(result.signed_distance_to(&moved), 0., epsilon = 0.0001);
+
+        let tangent = transformation.transform_vector(&Vector3::new(1., 0., 0.));
+        assert_relative_eq!(result.normal.dot(&tangent), 0., epsilon = 0.0001);
+        assert_relative_eq!(result.normal.norm(), 1., epsilon = 0.0001);
+
+        assert!(!plane.transform(&Matrix::zeros(), &mut result));
     }
 }
@@ -0,0 +1,313 @@
+use crate::{Number, Vector3, Matrix, coordiante_system::CoordinateSytem3, vector::{TToolVector3, TToolMatrix}, plane::Plane, camera::{Viewport, EDepthRange}};
+
+/// 射线与三角形求交结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntersectionInfo {
+    /// 交点重心坐标 - 对应第二个顶点的权重
+    pub bu: Number,
+    /// 交点重心坐标 - 对应第三个顶点的权重
+    pub bv: Number,
+    /// 沿射线方向到交点的距离
+    pub distance: Number,
+}
+
+/// 射线
+/// * `direction` 单位方向
+/// * `length` 有效长度, 超出该距离的交点视为未相交
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3,
+    pub length: Number,
+}
+
+impl Default for Ray {
+    fn default() -> Self {
+        Self { origin: Vector3::zeros(), direction: Vector3::new(0., 0., 1.), length: Number::MAX }
+    }
+}
+
+impl Ray {
+    pub fn new(origin: Vector3, direction: Vector3, length: Number) -> Self {
+        Self { origin, direction, length }
+    }
+
+    /// 从 from 指向 to 的射线, 长度为两点距离
+    pub fn from_to(from: &Vector3, to: &Vector3) -> Self {
+        let offset = to - from;
+        let length = offset.norm();
+        let direction = if length > 0. { offset / length } else { Vector3::zeros() };
+        Self { origin: *from, direction, length }
+    }
+
+    /// 屏幕拾取射线 - 起点位于近平面, 方向指向远处
+    /// * `x` `y` 屏幕像素坐标, 原点在左上角
+    /// * `world` 射线所在空间到世界空间的矩阵, 世界空间拾取传单位矩阵
+    /// * `view` `projection` 相机视图与投影矩阵
+    /// * `reverse_z` 投影是否为反向深度 (近平面深度为 1)
+    /// * `depth_range` 投影输出的深度范围
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_screen(x: Number, y: Number, viewport: &Viewport, world: &Matrix, view: &Matrix, projection: &Matrix, reverse_z: bool, depth_range: EDepthRange) -> Option<Self> {
+        let mut result = Self::default();
+        if result.unproject_mut(x, y, viewport, world, view, projection, reverse_z, depth_range) {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// 以屏幕坐标重设射线, 矩阵不可逆时返回 false
+    #[allow(clippy::too_many_arguments)]
+    pub fn unproject_mut(&mut self, x: Number, y: Number, viewport: &Viewport, world: &Matrix, view: &Matrix, projection: &Matrix, reverse_z: bool, depth_range: EDepthRange) -> bool {
+        let mut matrix = projection * view * world;
+        if !CoordinateSytem3::try_inverse_mut(&mut matrix) {
+            return false;
+        }
+
+        // 近平面与近平面后方一点, 取中间深度避免无穷远平面时齐次坐标 w 为 0
+        let near_depth = if reverse_z { 1. } else { 0. };
+
+        let mut near = Vector3::zeros();
+        let mut inner = Vector3::zeros();
+        viewport.unproject_with_inverse(x, y, near_depth, &matrix, depth_range, &mut near);
+        viewport.unproject_with_inverse(x, y, 0.5, &matrix, depth_range, &mut inner);
+
+        let direction = inner - near;
+        let length = direction.norm();
+        if length <= 0. || !length.is_finite() {
+            return false;
+        }
+
+        self.origin.copy_from(&near);
+        self.direction.copy_from(&(direction / length));
+        self.length = Number::MAX;
+        true
+    }
+
+    /// 射线经矩阵变换后的射线, 长度按方向缩放同步变化
+    pub fn transform(&self, transformation: &Matrix, result: &mut Ray) {
+        let mut origin = Vector3::zeros();
+        let mut direction = Vector3::zeros();
+        CoordinateSytem3::transform_coordinates(&self.origin, transformation, &mut origin);
+        CoordinateSytem3::transform_normal(&self.direction, transformation, &mut direction);
+
+        let scale = direction.norm();
+        result.origin.copy_from(&origin);
+        if scale > 0. {
+            result.direction.copy_from(&(direction / scale));
+        } else {
+            result.direction.fill(0.);
+        }
+        result.length = if self.length == Number::MAX { Number::MAX } else { self.length * scale };
+    }
+
+    /// 射线上距离为 distance 的点
+    pub fn point_at(&self, distance: Number, result: &mut Vector3) {
+        result.copy_from(&(self.origin + self.direction * distance));
+    }
+
+    /// 射线与平面求交, 返回交点距离
+    pub fn intersects_plane(&self, plane: &Plane) -> Option<Number> {
+        let dot = plane.normal.dot(&self.direction);
+        if dot.abs() < Number::EPSILON {
+            return None;
+        }
+        let distance = -plane.dot_coordinate2(&self.origin) / dot;
+        if distance < 0. || distance > self.length {
+            None
+        } else {
+            Some(distance)
+        }
+    }
+
+    /// 射线与球求交, 返回最近交点距离 - 起点在球内时为 0
+    pub fn intersects_sphere(&self, center: &Vector3, radius: Number) -> Option<Number> {
+        let offset = self.origin - center;
+        let c = offset.norm_squared() - radius * radius;
+        if c <= 0. {
+            return Some(0.);
+        }
+        let b = offset.dot(&self.direction);
+        if b > 0. {
+            return None;
+        }
+        let discriminant = b * b - c;
+        if discriminant < 0. {
+            return None;
+        }
+        let distance = -b - discriminant.sqrt();
+        if distance > self.length {
+            None
+        } else {
+            Some(distance)
+        }
+    }
+
+    /// 射线与轴对齐包围盒求交 (slab), 返回最近交点距离 - 起点在盒内时为 0
+    pub fn intersects_aabb(&self, minimum: &Vector3, maximum: &Vector3) -> Option<Number> {
+        let mut near: Number = 0.;
+        let mut far: Number = self.length;
+
+        for i in 0..3 {
+            let origin = self.origin[i];
+            let direction = self.direction[i];
+            if direction.abs() < Number::EPSILON {
+                if origin < minimum[i] || origin > maximum[i] {
+                    return None;
+                }
+            } else {
+                let inv = 1. / direction;
+                let mut t0 = (minimum[i] - origin) * inv;
+                let mut t1 = (maximum[i] - origin) * inv;
+                if t0 > t1 {
+                    std::mem::swap(&mut t0, &mut t1);
+                }
+                near = near.max(t0);
+                far = far.min(t1);
+                if near > far {
+                    return None;
+                }
+            }
+        }
+
+        Some(near)
+    }
+
+    /// 射线与三角形求交 (Möller–Trumbore, 双面)
+    /// * 交点 = p0 * (1 - bu - bv) + p1 * bu + p2 * bv
+    pub fn intersects_triangle(&self, p0: &Vector3, p1: &Vector3, p2: &Vector3) -> Option<IntersectionInfo> {
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+        let pvec = self.direction.cross(&edge2);
+        let det = edge1.dot(&pvec);
+        if det.abs() < Number::EPSILON {
+            return None;
+        }
+
+        let inv_det = 1. / det;
+        let tvec = self.origin - p0;
+        let bu = tvec.dot(&pvec) * inv_det;
+        if !(0. ..=1.).contains(&bu) {
+            return None;
+        }
+
+        let qvec = tvec.cross(&edge1);
+        let bv = self.direction.dot(&qvec) * inv_det;
+        if bv < 0. || bu + bv > 1. {
+            return None;
+        }
+
+        let distance = edge2.dot(&qvec) * inv_det;
+        if distance < 0. || distance > self.length {
+            return None;
+        }
+
+        Some(IntersectionInfo { bu, bv, distance })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Matrix, Isometry3, coordiante_system::CoordinateSytem3, vector::TToolMatrix, camera::{Viewport, TPerspectiveCameraTool, TOrthographicCameraTool, EDepthRange}, plane::Plane};
+
+    use super::Ray;
+
+    fn view_matrix(coord: &CoordinateSytem3, eye: &Vector3, target: &Vector3) -> Matrix {
+        let mut view = Isometry3::identity();
+        coord.lookat(eye, target, &Vector3::y(), &mut view);
+        view.to_homogeneous()
+    }
+
+    #[test]
+    fn test_ray_from_screen() {
+        let viewport = Viewport::new(100., 50., 800., 600.);
+        let eye = Vector3::new(0., 0., -10.);
+        let target = Vector3::new(0., 0., 0.);
+
+        // 左手系
+        let coord = CoordinateSytem3::left();
+        let view = view_matrix(&coord, &eye, &target);
+        let projection = CoordinateSytem3::perspective_lh(0.8, 800. / 600., 0.1, 1000., true, EDepthRange::ZeroToOne);
+        let ray = Ray::from_screen(500., 350., &viewport, &Matrix::identity(), &view, &projection, false, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+        assert_relative_eq!(ray.origin, Vector3::new(0., 0., -9.9), epsilon = 0.0001);
+
+        // 右手系
+        let coord = CoordinateSytem3::right();
+        let view = view_matrix(&coord, &eye, &target);
+        let projection = CoordinateSytem3::perspective_rh(0.8, 800. / 600., 0.1, 1000., true, EDepthRange::ZeroToOne);
+        let ray = Ray::from_screen(500., 350., &viewport, &Matrix::identity(), &view, &projection, false, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+
+        // 屏幕左上角射线偏向左上
+        let ray = Ray::from_screen(100., 50., &viewport, &Matrix::identity(), &view, &projection, false, EDepthRange::ZeroToOne).unwrap();
+        assert!(ray.direction.y > 0.);
+
+        // 反向深度
+        let projection = CoordinateSytem3::perspective_for_reverse_rh(0.8, 800. / 600., 0.1, 1000., true, EDepthRange::ZeroToOne);
+        let ray = Ray::from_screen(500., 350., &viewport, &Matrix::identity(), &view, &projection, true, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+        assert_relative_eq!(ray.origin, Vector3::new(0., 0., -9.9), epsilon = 0.001);
+
+        // 无限远反向深度
+        let projection = CoordinateSytem3::perspective_infinite_reverse_rh(0.8, 800. / 600., 0.1, true, EDepthRange::ZeroToOne);
+        let ray = Ray::from_screen(500., 350., &viewport, &Matrix::identity(), &view, &projection, true, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+        assert_relative_eq!(ray.origin, Vector3::new(0., 0., -9.9), epsilon = 0.001);
+
+        // [-1, 1] 深度范围
+        let projection = CoordinateSytem3::perspective_rh(0.8, 800. / 600., 0.1, 1000., true, EDepthRange::NegativeOneToOne);
+        let ray = Ray::from_screen(500., 350., &viewport, &Matrix::identity(), &view, &projection, false, EDepthRange::NegativeOneToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+        assert_relative_eq!(ray.origin, Vector3::new(0., 0., -9.9), epsilon = 0.001);
+
+        // 正交
+        let coord = CoordinateSytem3::left();
+        let view = view_matrix(&coord, &eye, &target);
+        let projection = CoordinateSytem3::orthographic_lh(-4., 4., -3., 3., 0.1, 1000., EDepthRange::ZeroToOne);
+        let ray = Ray::from_screen(100., 50., &viewport, &Matrix::identity(), &view, &projection, false, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.direction, Vector3::new(0., 0., 1.), epsilon = 0.0001);
+        assert_relative_eq!(ray.origin, Vector3::new(-4., 3., -9.9), epsilon = 0.0001);
+
+        // 局部空间拾取
+        let mut world = Matrix::identity();
+        world.append_translation_mut(&Vector3::new(0., 0., 5.));
+        let ray = Ray::from_screen(500., 350., &viewport, &world, &view, &projection, false, EDepthRange::ZeroToOne).unwrap();
+        assert_relative_eq!(ray.origin, Vector3::new(0., 0., -14.9), epsilon = 0.0001);
+
+        assert!(Ray::from_screen(500., 350., &viewport, &Matrix::zeros(), &view, &projection, false, EDepthRange::ZeroToOne).is_none());
+    }
+
+    #[test]
+    fn test_ray_primitives() {
+        let ray = Ray::new(Vector3::new(0., 0., -10.), Vector3::new(0., 0., 1.), 100.);
+
+        let plane = Plane::new(0., 0., 1., -2.);
+        assert_relative_eq!(ray.intersects_plane(&plane).unwrap(), 12.);
+
+        assert_relative_eq!(ray.intersects_sphere(&Vector3::new(0., 0., 0.), 2.).unwrap(), 8.);
+        assert!(ray.intersects_sphere(&Vector3::new(0., 3., 0.), 2.).is_none());
+
+        assert_relative_eq!(ray.intersects_aabb(&Vector3::new(-1., -1., -1.), &Vector3::new(1., 1., 1.)).unwrap(), 9.);
+        assert!(ray.intersects_aabb(&Vector3::new(2., -1., -1.), &Vector3::new(3., 1., 1.)).is_none());
+
+        let hit = ray.intersects_triangle(&Vector3::new(-1., -1., 5.), &Vector3::new(3., -1., 5.), &Vector3::new(-1., 3., 5.)).unwrap();
+        assert_relative_eq!(hit.distance, 15.);
+        assert_relative_eq!(hit.bu, 0.25);
+        assert_relative_eq!(hit.bv, 0.25);
+        assert!(ray.intersects_triangle(&Vector3::new(1., 1., 5.), &Vector3::new(3., 1., 5.), &Vector3::new(1., 3., 5.)).is_none());
+
+        let short = Ray::new(ray.origin, ray.direction, 10.);
+        assert!(short.intersects_triangle(&Vector3::new(-1., -1., 5.), &Vector3::new(3., -1., 5.), &Vector3::new(-1., 3., 5.)).is_none());
+
+        let mut transformed = Ray::default();
+        let mut transformation = Matrix::new_scaling(2.);
+        transformation.append_translation_mut(&Vector3::new(1., 0., 0.));
+        short.transform(&transformation, &mut transformed);
+        assert_relative_eq!(transformed.origin, Vector3::new(1., 0., -20.));
+        assert_relative_eq!(transformed.direction, Vector3::new(0., 0., 1.));
+        assert_relative_eq!(transformed.length, 20.);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{Matrix, coordiante_system::CoordinateSytem3, vector::TToolMatrix, hierarchy::{Hierarchy, NodeId}};
+
+/// 骨骼 - 绑定到层级中的节点
+#[derive(Debug, Clone)]
+pub struct Bone {
+    pub name: String,
+    pub node: NodeId,
+    /// 绑定姿势下 模型空间 -> 骨骼空间
+    pub inverse_bind: Matrix,
+}
+
+/// 骨架
+/// * 蒙皮矩阵 = 网格世界矩阵逆 * 骨骼世界矩阵 * 逆绑定矩阵 (同 glTF)
+/// * 计算蒙皮矩阵前需 `Hierarchy::update`
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    bones: Vec<Bone>,
+    palette: Vec<Matrix>,
+}
+
+impl Skeleton {
+    /// 每根骨骼在骨骼纹理中占用的 RGBA 像素数
+    pub const TEXELS_PER_BONE: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加骨骼, 返回骨骼下标
+    pub fn add_bone(&mut self, name: &str, node: NodeId, inverse_bind: Matrix) -> usize {
+        self.bones.push(Bone { name: String::from(name), node, inverse_bind });
+        self.palette.push(Matrix::identity());
+        self.bones.len() - 1
+    }
+
+    pub fn bones(&self) -> &[Bone] {
+        &self.bones
+    }
+
+    pub fn bone(&self, index: usize) -> Option<&Bone> {
+        self.bones.get(index)
+    }
+
+    pub fn bone_mut(&mut self, index: usize) -> Option<&mut Bone> {
+        self.bones.get_mut(index)
+    }
+
+    pub fn find_bone(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|bone| bone.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bones.is_empty()
+    }
+
+    /// 以层级当前姿势作为绑定姿势, 计算各骨骼逆绑定矩阵
+    /// * mesh_world 为绑定时网格的世界矩阵, None 为单位矩阵
+    /// * 节点无效或矩阵不可逆时返回 false, 此时所有骨骼的逆绑定矩阵保持不变
+    pub fn set_bind_pose(&mut self, hierarchy: &Hierarchy, mesh_world: Option<&Matrix>) -> bool {
+        let mut inverse_binds = Vec::with_capacity(self.bones.len());
+        for bone in self.bones.iter() {
+            let Some(world) = hierarchy.world_matrix(bone.node) else {
+                return false;
+            };
+            let mut inverse = *world;
+            if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+                return false;
+            }
+            // (mesh_world⁻¹ * world)⁻¹ = world⁻¹ * mesh_world
+            inverse_binds.push(match mesh_world {
+                Some(mesh_world) => inverse * mesh_world,
+                None => inverse,
+            });
+        }
+
+        for (bone, inverse_bind) in self.bones.iter_mut().zip(inverse_binds) {
+            bone.inverse_bind = inverse_bind;
+        }
+        true
+    }
+
+    /// 计算蒙皮矩阵
+    /// * mesh_world 为网格当前世界矩阵, None 为单位矩阵
+    /// * 节点无效或网格矩阵不可逆时返回 false, 此时蒙皮矩阵保持不变
+    pub fn calc_palette(&mut self, hierarchy: &Hierarchy, mesh_world: Option<&Matrix>) -> bool {
+        let mesh_inverse = match mesh_world {
+            Some(mesh_world) => {
+                let mut inverse = *mesh_world;
+                if !CoordinateSytem3::try_inverse_mut(&mut inverse) {
+                    return false;
+                }
+                Some(inverse)
+            },
+            None => None,
+        };
+        if self.bones.iter().any(|bone| hierarchy.world_matrix(bone.node).is_none()) {
+            return false;
+        }
+
+        for (bone, matrix) in self.bones.iter().zip(self.palette.iter_mut()) {
+            let Some(world) = hierarchy.world_matrix(bone.node) else {
+                continue;
+            };
+            *matrix = match mesh_inverse {
+                Some(mesh_inverse) => mesh_inverse * world * bone.inverse_bind,
+                None => world * bone.inverse_bind,
+            };
+        }
+        true
+    }
+
+    /// 蒙皮矩阵 - `calc_palette` 之后有效
+    pub fn palette(&self) -> &[Matrix] {
+        &self.palette
+    }
+
+    /// 将蒙皮矩阵按列主序依次写入缓冲, 每根骨骼 16 个 f32
+    /// * 可直接作为 uniform / storage buffer 的 mat4 数组
+    /// * 也可作为宽 `TEXELS_PER_BONE` 高为骨骼数的 RGBA 浮点纹理, 每行一根骨骼
+    /// * 启用 `f64` 时逐分量转为 f32, GPU 端不支持双精度
+    #[allow(clippy::unnecessary_cast)]
+    pub fn pack_palette(&self, result: &mut Vec<f32>) {
+        result.clear();
+        result.reserve(self.palette.len() * 16);
+        for matrix in self.palette.iter() {
+            result.extend(matrix.iter().map(|value| *value as f32));
+        }
+    }
+
+    /// 骨骼纹理尺寸 (宽, 高)
+    pub fn palette_texture_size(&self) -> (usize, usize) {
+        (Self::TEXELS_PER_BONE, self.palette.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Vector3, Matrix, Rotation3, coordiante_system::CoordinateSytem3, vector::TToolVector3, transform::translated, hierarchy::Hierarchy, consts::FRAC_PI_2};
+
+    use super::Skeleton;
+
+    #[test]
+    fn test_skinning_palette() {
+        let mut hierarchy = Hierarchy::new();
+        let root = hierarchy.insert(translated(0., 0., 0.), None).unwrap();
+        let elbow = hierarchy.insert(translated(0., 2., 0.), Some(root)).unwrap();
+        hierarchy.update();
+
+        let mut skeleton = Skeleton::new();
+        skeleton.add_bone("root", root, Matrix::identity());
+        skeleton.add_bone("elbow", elbow, Matrix::identity());
+        assert_eq!(skeleton.find_bone("elbow"), Some(1));
+        assert!(skeleton.set_bind_pose(&hierarchy, None));
+
+        // 绑定姿势下蒙皮矩阵为单位矩阵
+        assert!(skeleton.calc_palette(&hierarchy, None));
+        for matrix in skeleton.palette() {
+            assert_relative_eq!(*matrix, Matrix::identity(), epsilon = 0.0001);
+        }
+
+        // 肘部绕 Z 旋转 90 度, 绑定在肘部上方的顶点随之旋转
+        hierarchy.transform_mut(elbow).unwrap().rotation_matrix_mut(&Rotation3::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2));
+        hierarchy.update();
+        assert!(skeleton.calc_palette(&hierarchy, None));
+        let mut vertex = Vector3::zeros();
+        CoordinateSytem3::transform_coordinates(&Vector3::new(0., 3., 0.), &skeleton.palette()[1], &mut vertex);
+        assert_relative_eq!(vertex, Vector3::new(-1., 2., 0.), epsilon = 0.0001);
+        CoordinateSytem3::transform_coordinates(&Vector3::new(0., 1., 0.), &skeleton.palette()[0], &mut vertex);
+        assert_relative_eq!(vertex, Vector3::new(0., 1., 0.), epsilon = 0.0001);
+
+        // 网格整体移动时蒙皮结果位于网格空间, 不受影响
+        let mut mesh_world = Matrix::identity();
+        mesh_world.append_translation_mut(&Vector3::new(5., 0., 0.));
+        hierarchy.transform_mut(root).unwrap().set_translation_from_floats(5., 0., 0.);
+        hierarchy.update();
+        assert!(skeleton.calc_palette(&hierarchy, Some(&mesh_world)));
+        CoordinateSytem3::transform_coordinates(&Vector3::new(0., 3., 0.), &skeleton.palette()[1], &mut vertex);
+        assert_relative_eq!(vertex, Vector3::new(-1., 2., 0.), epsilon = 0.0001);
+
+        // 删除节点后失败, 已有数据保持不变
+        let palette = skeleton.palette().to_vec();
+        let inverse_binds: Vec<Matrix> = skeleton.bones().iter().map(|bone| bone.inverse_bind).collect();
+        hierarchy.remove(elbow);
+        assert!(!skeleton.calc_palette(&hierarchy, None));
+        assert_eq!(skeleton.palette(), &palette[..]);
+        assert!(!skeleton.set_bind_pose(&hierarchy, None));
+        for (bone, inverse_bind) in skeleton.bones().iter().zip(inverse_binds.iter()) {
+            assert_eq!(bone.inverse_bind, *inverse_bind);
+        }
+    }
+
+    #[test]
+    fn test_pack_palette() {
+        let mut hierarchy = Hierarchy::new();
+        let node = hierarchy.insert(translated(1., 2., 3.), None).unwrap();
+        hierarchy.update();
+
+        let mut skeleton = Skeleton::new();
+        skeleton.add_bone("a", node, Matrix::identity());
+        skeleton.add_bone("b", node, Matrix::identity());
+        skeleton.calc_palette(&hierarchy, None);
+
+        let mut buffer: Vec<f32> = vec![];
+        skeleton.pack_palette(&mut buffer);
+        assert_eq!(buffer.len(), 32);
+        assert_eq!(skeleton.palette_texture_size(), (4, 2));
+        // 列主序, 位移位于第 4 列
+        assert_eq!(&buffer[12..16], &[1., 2., 3., 1.]);
+        assert_eq!(&buffer[28..32], &[1., 2., 3., 1.]);
+    }
+}
@@ -0,0 +1,494 @@
+use crate::{Number, Vector3, Quaternion, coordiante_system::CoordinateSytem3, vector::{TToolRotation, TToolVector3}, transform::Transform3};
+
+/// 路径上的标架
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplineFrame {
+    pub position: Vector3,
+    /// 单位切线
+    pub tangent: Vector3,
+    pub normal: Vector3,
+    /// tangent × normal
+    pub binormal: Vector3,
+}
+
+/// 三次样条路径
+/// * 每段以幂基系数 c0 + c1 u + c2 u² + c3 u³ 保存, u ∈ [0, 1], 参数 t = 段下标 + u
+/// * 构造时每段均匀采样建立弧长表, 按距离查询时在表内插值
+/// * 同时在采样点上以平行移动计算法线, 初始法线为 up 在切线垂面上的投影
+/// * 闭合路径平行移动一周后法线与起点不一致, 将差角按弧长分摊到各采样点, 使首尾法线吻合
+#[derive(Debug, Clone)]
+pub struct SplinePath {
+    segments: Vec<[Vector3; 4]>,
+    closed: bool,
+    samples_per_segment: usize,
+    /// 采样点累计弧长
+    distances: Vec<Number>,
+    /// 采样点平行移动法线
+    normals: Vec<Vector3>,
+}
+
+impl SplinePath {
+    /// Catmull-Rom 样条, 经过所有控制点
+    /// * 非闭合时首尾切线由外推控制点得到
+    /// * 控制点少于 2 个时返回 None
+    pub fn catmull_rom(points: &[Vector3], closed: bool, samples_per_segment: usize) -> Option<Self> {
+        let count = points.len();
+        if count < 2 {
+            return None;
+        }
+        let point = |index: isize| -> Vector3 {
+            if closed {
+                points[index.rem_euclid(count as isize) as usize]
+            } else if index < 0 {
+                points[0] * 2. - points[1]
+            } else if index as usize >= count {
+                points[count - 1] * 2. - points[count - 2]
+            } else {
+                points[index as usize]
+            }
+        };
+
+        let segment_count = if closed { count } else { count - 1 };
+        let segments = (0..segment_count as isize).map(|i| {
+            let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+            [
+                p1,
+                (p2 - p0) * 0.5,
+                (p0 * 2. - p1 * 5. + p2 * 4. - p3) * 0.5,
+                (-p0 + p1 * 3. - p2 * 3. + p3) * 0.5,
+            ]
+        }).collect();
+        Some(Self::new(segments, closed, samples_per_segment))
+    }
+
+    /// 三次贝塞尔样条, 控制点为 P0 C0 C1 P1 C2 C3 P2 ...
+    /// * 控制点数量不为 3n + 1 (n >= 1) 时返回 None
+    pub fn cubic_bezier(points: &[Vector3], samples_per_segment: usize) -> Option<Self> {
+        if points.len() < 4 || (points.len() - 1) % 3 != 0 {
+            return None;
+        }
+        let segments = points.windows(4).step_by(3).map(|b| {
+            [
+                b[0],
+                (b[1] - b[0]) * 3.,
+                (b[0] - b[1] * 2. + b[2]) * 3.,
+                -b[0] + b[1] * 3. - b[2] * 3. + b[3],
+            ]
+        }).collect();
+        Some(Self::new(segments, false, samples_per_segment))
+    }
+
+    /// Hermite 样条, 每个控制点对应一条切线 (每段参数变化量 1)
+    /// * 控制点少于 2 个或切线数量不符时返回 None
+    pub fn hermite(points: &[Vector3], tangents: &[Vector3], samples_per_segment: usize) -> Option<Self> {
+        if points.len() < 2 || points.len() != tangents.len() {
+            return None;
+        }
+        let segments = (0..points.len() - 1).map(|i| {
+            let (p1, m1, p2, m2) = (points[i], tangents[i], points[i + 1], tangents[i + 1]);
+            [
+                p1,
+                m1,
+                p1 * -3. - m1 * 2. + p2 * 3. - m2,
+                p1 * 2. + m1 - p2 * 2. + m2,
+            ]
+        }).collect();
+        Some(Self::new(segments, false, samples_per_segment))
+    }
+
+    fn new(segments: Vec<[Vector3; 4]>, closed: bool, samples_per_segment: usize) -> Self {
+        let samples_per_segment = samples_per_segment.max(1);
+        let mut path = Self { segments, closed, samples_per_segment, distances: Vec::new(), normals: Vec::new() };
+
+        let sample_count = path.segments.len() * samples_per_segment + 1;
+        path.distances.reserve(sample_count);
+        let mut last = path.point_at_parameter(0.);
+        let mut distance = 0.;
+        for index in 0..sample_count {
+            let point = path.point_at_parameter(path.sample_parameter(index));
+            distance += (point - last).norm();
+            path.distances.push(distance);
+            last = point;
+        }
+
+        path.set_up(&Vector3::y());
+        path
+    }
+
+    /// 以 up 重新计算平行移动法线, up 与起点切线平行时改用 X 轴
+    pub fn set_up(&mut self, up: &Vector3) {
+        let sample_count = self.distances.len();
+        self.normals.clear();
+        self.normals.reserve(sample_count);
+
+        let mut tangent = self.tangent_at_parameter(0.);
+        let mut normal = (up - tangent * tangent.dot(up)).try_normalize(Number::EPSILON)
+            .or_else(|| (Vector3::x() - tangent * tangent.x).try_normalize(Number::EPSILON))
+            .unwrap_or_else(Vector3::z);
+        self.normals.push(normal);
+        for index in 1..sample_count {
+            let next = self.tangent_at_parameter(self.sample_parameter(index));
+            // 以切线间最小旋转移动法线
+            let rotation = CoordinateSytem3::quaternion_from_unit_vector(&nalgebra::Unit::new_unchecked(tangent), &next);
+            normal = rotation * normal;
+            normal = (normal - next * next.dot(&normal)).try_normalize(Number::EPSILON).unwrap_or(normal);
+            self.normals.push(normal);
+            tangent = next;
+        }
+
+        if self.closed {
+            self.match_seam();
+        }
+    }
+
+    /// 闭合路径首尾法线差角按弧长分摊, 终点法线转回起点法线
+    fn match_seam(&mut self) {
+        let (Some(first), Some(last)) = (self.normals.first().copied(), self.normals.last().copied()) else {
+            return;
+        };
+        let length = self.length();
+        let tangent = self.tangent_at_parameter(0.);
+        let angle = tangent.dot(&last.cross(&first)).atan2(last.dot(&first));
+        if length <= 0. || angle.abs() < Number::EPSILON {
+            return;
+        }
+        for index in 1..self.normals.len() {
+            let Some(axis) = nalgebra::Unit::try_new(self.tangent_at_parameter(self.sample_parameter(index)), Number::EPSILON) else {
+                continue;
+            };
+            let rotation = Quaternion::from_axis_angle(&axis, angle * self.distances[index] / length);
+            self.normals[index] = rotation * self.normals[index];
+        }
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// 总弧长
+    pub fn length(&self) -> Number {
+        self.distances.last().copied().unwrap_or(0.)
+    }
+
+    /// 参数 t ∈ [0, segment_count] 处的位置
+    pub fn point_at_parameter(&self, t: Number) -> Vector3 {
+        let ([c0, c1, c2, c3], u) = self.segment(t);
+        c0 + (c1 + (c2 + c3 * u) * u) * u
+    }
+
+    /// 参数 t 处的导数
+    pub fn derivative_at_parameter(&self, t: Number) -> Vector3 {
+        let ([_, c1, c2, c3], u) = self.segment(t);
+        c1 + (c2 * 2. + c3 * (3. * u)) * u
+    }
+
+    /// 参数 t 处的单位切线, 导数为零时返回零向量
+    pub fn tangent_at_parameter(&self, t: Number) -> Vector3 {
+        self.derivative_at_parameter(t).try_normalize(Number::EPSILON).unwrap_or_else(Vector3::zeros)
+    }
+
+    /// 弧长距离对应的参数, 距离限制在 [0, length]
+    pub fn parameter_at_distance(&self, distance: Number) -> Number {
+        let distance = distance.clamp(0., self.length());
+        let index = self.distances.partition_point(|value| *value <= distance);
+        if index == 0 {
+            return 0.;
+        }
+        if index >= self.distances.len() {
+            return self.segments.len() as Number;
+        }
+        let (start, end) = (self.distances[index - 1], self.distances[index]);
+        let amount = if end > start { (distance - start) / (end - start) } else { 0. };
+        (index - 1) as Number / self.samples_per_segment as Number + amount / self.samples_per_segment as Number
+    }
+
+    /// 参数对应的弧长距离
+    pub fn distance_at_parameter(&self, t: Number) -> Number {
+        let position = t.clamp(0., self.segments.len() as Number) * self.samples_per_segment as Number;
+        let index = (position.floor() as usize).min(self.distances.len() - 1);
+        match self.distances.get(index + 1) {
+            Some(next) => self.distances[index] + (next - self.distances[index]) * (position - index as Number),
+            None => self.distances[index],
+        }
+    }
+
+    pub fn point_at_distance(&self, distance: Number) -> Vector3 {
+        self.point_at_parameter(self.parameter_at_distance(distance))
+    }
+
+    pub fn tangent_at_distance(&self, distance: Number) -> Vector3 {
+        self.tangent_at_parameter(self.parameter_at_distance(distance))
+    }
+
+    /// 平行移动标架, 无扭转
+    pub fn frame_at_distance(&self, distance: Number) -> SplineFrame {
+        let t = self.parameter_at_distance(distance);
+        let position = t * self.samples_per_segment as Number;
+        let index = (position.floor() as usize).min(self.normals.len() - 1);
+        let next = (index + 1).min(self.normals.len() - 1);
+        let normal = self.normals[index].lerp(&self.normals[next], position - index as Number);
+
+        let tangent = self.tangent_at_parameter(t);
+        let normal = (normal - tangent * tangent.dot(&normal)).try_normalize(Number::EPSILON).unwrap_or(self.normals[index]);
+        SplineFrame { position: self.point_at_parameter(t), tangent, normal, binormal: tangent.cross(&normal) }
+    }
+
+    /// Frenet 标架, 法线指向曲率中心; 直线处曲率为零返回 None
+    pub fn frenet_frame_at_distance(&self, distance: Number) -> Option<SplineFrame> {
+        let t = self.parameter_at_distance(distance);
+        let ([_, c1, c2, c3], u) = self.segment(t);
+        let first = c1 + (c2 * 2. + c3 * (3. * u)) * u;
+        let second = c2 * 2. + c3 * (6. * u);
+        let tangent = first.try_normalize(Number::EPSILON)?;
+        let binormal = first.cross(&second).try_normalize(Number::EPSILON)?;
+        Some(SplineFrame { position: self.point_at_parameter(t), tangent, normal: binormal.cross(&tangent), binormal })
+    }
+
+    /// 路径上距 point 最近的点, 返回其弧长距离
+    /// * 在最近采样点两侧的区间内三分搜索细化
+    pub fn closest_point(&self, point: &Vector3, result: &mut Vector3) -> Number {
+        let squared = |t: Number| (self.point_at_parameter(t) - point).norm_squared();
+        let nearest = (0..self.distances.len())
+            .map(|index| (index, squared(self.sample_parameter(index))))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+
+        let mut low = self.sample_parameter(nearest.saturating_sub(1));
+        let mut high = self.sample_parameter((nearest + 1).min(self.distances.len() - 1));
+        for _ in 0..40 {
+            let a = low + (high - low) / 3.;
+            let b = high - (high - low) / 3.;
+            if squared(a) < squared(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+
+        let t = (low + high) * 0.5;
+        *result = self.point_at_parameter(t);
+        self.distance_at_parameter(t)
+    }
+
+    fn sample_parameter(&self, index: usize) -> Number {
+        index as Number / self.samples_per_segment as Number
+    }
+
+    /// 参数所在段的系数与段内参数
+    fn segment(&self, t: Number) -> ([Vector3; 4], Number) {
+        let count = self.segments.len();
+        let t = t.clamp(0., count as Number);
+        let index = (t.floor() as usize).min(count - 1);
+        (self.segments[index], t - index as Number)
+    }
+}
+
+/// 路径跟随到达端点后的行为
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EPathLoop {
+    /// 停在端点
+    #[default]
+    Clamp,
+    /// 回到起点, 闭合路径首尾相接
+    Loop,
+    /// 往返
+    PingPong,
+}
+
+/// 跟随时的向上方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EPathUp {
+    /// 坐标系向上轴, 不随路径倾斜
+    #[default]
+    World,
+    /// 路径平行移动法线, 随路径扭转
+    Frame,
+}
+
+/// 路径跟随 - 以恒定速度沿弧长移动, 驱动 Transform3 的位置与朝向
+/// * 朝向使坐标系前方对齐移动方向的切线, 向上方向由 up_mode 决定
+#[derive(Debug, Clone)]
+pub struct PathFollower {
+    coord: CoordinateSytem3,
+    /// 速度 (单位/秒)
+    pub speed: Number,
+    /// 当前弧长距离
+    pub distance: Number,
+    pub loop_mode: EPathLoop,
+    pub up_mode: EPathUp,
+    /// 往返时的移动方向 1 或 -1
+    direction: Number,
+}
+
+impl PathFollower {
+    pub fn new(coord: CoordinateSytem3, speed: Number) -> Self {
+        Self { coord, speed, distance: 0., loop_mode: EPathLoop::Clamp, up_mode: EPathUp::World, direction: 1. }
+    }
+
+    pub fn coordinate_system(&self) -> &CoordinateSytem3 {
+        &self.coord
+    }
+
+    /// 是否正在沿路径反向移动
+    pub fn is_reversed(&self) -> bool {
+        self.direction < 0.
+    }
+
+    /// 按时间步长前进
+    pub fn update(&mut self, path: &SplinePath, delta_time: Number) {
+        let length = path.length();
+        self.distance += self.speed * self.direction * delta_time;
+        if length <= 0. {
+            self.distance = 0.;
+            return;
+        }
+        match self.loop_mode {
+            EPathLoop::Clamp => self.distance = self.distance.clamp(0., length),
+            EPathLoop::Loop => self.distance = self.distance.rem_euclid(length),
+            EPathLoop::PingPong => {
+                let cycle = self.distance.div_euclid(length);
+                self.distance = self.distance.rem_euclid(length);
+                if (cycle as i64).rem_euclid(2) == 1 {
+                    self.distance = length - self.distance;
+                    self.direction = -self.direction;
+                }
+            },
+        }
+    }
+
+    /// 将当前位置与朝向写入 transform
+    pub fn apply(&self, path: &SplinePath, transform: &mut Transform3) {
+        let frame = path.frame_at_distance(self.distance);
+        transform.translation_mut(&frame.position);
+
+        let heading = frame.tangent * self.direction;
+        let forward = self.coord.forward();
+        if heading.norm_squared() < Number::EPSILON {
+            return;
+        }
+        let align = CoordinateSytem3::quaternion_from_unit_vector(&nalgebra::Unit::new_unchecked(forward), &heading);
+
+        // 绕切线滚转, 使向上方向对齐目标向上方向
+        let up = match self.up_mode {
+            EPathUp::World => self.coord.up(),
+            EPathUp::Frame => frame.normal,
+        };
+        let target_up = up - heading * heading.dot(&up);
+        let current_up = align * self.coord.up();
+        let rotation = if target_up.norm_squared() > Number::EPSILON {
+            let angle = heading.dot(&current_up.cross(&target_up)).atan2(current_up.dot(&target_up));
+            Quaternion::from_axis_angle(&nalgebra::Unit::new_unchecked(heading), angle) * align
+        } else {
+            align
+        };
+        transform.rotation_quaternion_mut(&rotation);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Number, Vector3, coordiante_system::CoordinateSytem3, vector::TToolVector3, transform::Transform3, consts::FRAC_PI_2};
+
+    use super::{SplinePath, PathFollower, EPathLoop};
+
+    #[test]
+    fn test_spline_arc_length() {
+        // 控制点间距不均匀的直线, 弧长参数化后中点均匀
+        let points = [Vector3::new(0., 0., 0.), Vector3::new(9., 0., 0.), Vector3::new(9.5, 0., 0.), Vector3::new(10., 0., 0.)];
+        let path = SplinePath::cubic_bezier(&points, 64).unwrap();
+        assert!(SplinePath::cubic_bezier(&points[..3], 64).is_none());
+        assert_relative_eq!(path.length(), 10., epsilon = 0.001);
+        assert_relative_eq!(path.point_at_distance(5.), Vector3::new(5., 0., 0.), epsilon = 0.01);
+        assert_relative_eq!(path.distance_at_parameter(path.parameter_at_distance(3.)), 3., epsilon = 0.001);
+        assert!(path.frenet_frame_at_distance(5.).is_none());
+
+        // Catmull-Rom 经过控制点
+        let points = [Vector3::new(0., 0., 0.), Vector3::new(1., 0., 1.), Vector3::new(2., 0., 0.), Vector3::new(3., 0., 1.)];
+        let path = SplinePath::catmull_rom(&points, false, 16).unwrap();
+        assert_eq!(path.segment_count(), 3);
+        for (index, point) in points.iter().enumerate() {
+            assert_relative_eq!(path.point_at_parameter(index as Number), *point, epsilon = 0.0001);
+        }
+        assert_eq!(SplinePath::catmull_rom(&points, true, 16).unwrap().segment_count(), 4);
+
+        // Hermite 近似四分之一圆
+        // 切线长度 4(√2 - 1) 为圆弧的三次近似
+        let k = 4. * ((2.0 as Number).sqrt() - 1.);
+        let path = SplinePath::hermite(&[Vector3::new(1., 0., 0.), Vector3::new(0., 0., 1.)], &[Vector3::new(0., 0., k), Vector3::new(-k, 0., 0.)], 64).unwrap();
+        assert_relative_eq!(path.length(), FRAC_PI_2, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_spline_queries() {
+        let k = 4. * ((2.0 as Number).sqrt() - 1.);
+        let path = SplinePath::hermite(&[Vector3::new(1., 0., 0.), Vector3::new(0., 0., 1.)], &[Vector3::new(0., 0., k), Vector3::new(-k, 0., 0.)], 64).unwrap();
+
+        // 最近点
+        let mut closest = Vector3::zeros();
+        let distance = path.closest_point(&Vector3::new(2., 0., 2.), &mut closest);
+        assert_relative_eq!(closest, Vector3::new(1., 0., 1.).normalize(), epsilon = 0.01);
+        assert_relative_eq!(distance, path.length() * 0.5, epsilon = 0.01);
+
+        // Frenet 法线指向圆心, 平行移动法线保持向上
+        let frenet = path.frenet_frame_at_distance(path.length() * 0.5).unwrap();
+        assert_relative_eq!(frenet.normal, -Vector3::new(1., 0., 1.).normalize(), epsilon = 0.01);
+        let frame = path.frame_at_distance(path.length() * 0.5);
+        assert_relative_eq!(frame.normal, Vector3::y(), epsilon = 0.0001);
+        assert_relative_eq!(frame.binormal, frame.tangent.cross(&frame.normal));
+    }
+
+    #[test]
+    fn test_spline_closed_seam() {
+        // 不规则的空间闭合环, 平行移动一周后法线有扭转差
+        let points = [Vector3::new(1., 0., 0.), Vector3::new(0., 2., 1.), Vector3::new(-1., 0., 2.), Vector3::new(0., -1., 0.5), Vector3::new(0.5, -0.5, -1.)];
+        let path = SplinePath::catmull_rom(&points, true, 32).unwrap();
+        let start = path.frame_at_distance(0.);
+        let end = path.frame_at_distance(path.length());
+        assert_relative_eq!(start.position, end.position, epsilon = 0.0001);
+        assert_relative_eq!(start.tangent, end.tangent, epsilon = 0.0001);
+        assert_relative_eq!(start.normal, end.normal, epsilon = 0.0001);
+
+        // 分摊后法线仍与切线垂直
+        for index in 0..10 {
+            let frame = path.frame_at_distance(path.length() * index as Number / 10.);
+            assert_relative_eq!(frame.normal.dot(&frame.tangent), 0., epsilon = 0.0001);
+            assert_relative_eq!(frame.normal.norm(), 1., epsilon = 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_path_follower() {
+        let path = SplinePath::catmull_rom(&[Vector3::new(0., 0., 0.), Vector3::new(10., 0., 0.)], false, 16).unwrap();
+        let coord = CoordinateSytem3::left();
+        let mut follower = PathFollower::new(coord, 2.);
+        let mut transform = Transform3::default();
+
+        follower.update(&path, 1.);
+        follower.apply(&path, &mut transform);
+        assert_relative_eq!(transform.translation(), Vector3::new(2., 0., 0.), epsilon = 0.001);
+        assert_relative_eq!(transform.rotation_matrix() * coord.forward(), Vector3::x(), epsilon = 0.0001);
+        assert_relative_eq!(transform.rotation_matrix() * coord.up(), Vector3::y(), epsilon = 0.0001);
+
+        follower.update(&path, 10.);
+        assert_relative_eq!(follower.distance, 10.);
+
+        follower.loop_mode = EPathLoop::Loop;
+        follower.update(&path, 1.);
+        assert_relative_eq!(follower.distance, 2., epsilon = 0.001);
+
+        // 往返时到达终点后调头
+        follower.loop_mode = EPathLoop::PingPong;
+        follower.update(&path, 5.);
+        assert!(follower.is_reversed());
+        assert_relative_eq!(follower.distance, 8., epsilon = 0.001);
+        follower.apply(&path, &mut transform);
+        assert_relative_eq!(transform.rotation_matrix() * coord.forward(), -Vector3::x(), epsilon = 0.0001);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Rotation3, Vector3, Number, Matrix, Quaternion, coordiante_system::CoordinateSytem3, vector::TToolRotation};
+use crate::{Rotation3, Vector3, Number, Matrix, Quaternion, coordiante_system::{CoordinateSytem3, EEulerOrder}, vector::{TToolRotation, TToolMatrix}};
 
 #[derive(Debug)]
 pub struct Transform3 {
@@ -8,6 +8,10 @@ pub struct Transform3 {
     scaling: Vector3,
     /// 旋转矩阵
     rotation: Rotation3,
+    /// 旋转中心 (局部空间), 未设置缩放中心时也作为缩放中心
+    pivot: Option<Vector3>,
+    /// 缩放中心 (局部空间)
+    scaling_pivot: Option<Vector3>,
     /// 仿射矩阵
     local_matrix: Matrix,
     dirty: bool,
@@ -25,13 +29,15 @@ impl Transform3 {
             translation: Vector3::zeros(),
             scaling: Vector3::new(1., 1., 1.),
             rotation: Rotation3::identity(),
+            pivot: None,
+            scaling_pivot: None,
             local_matrix: Matrix::identity(),
             dirty: false,
         }
     }
 
     pub fn translation(&self) -> Vector3 {
-        self.translation.clone()
+        self.translation
     }
 
     pub fn translation_mut(&mut self, rhs: &Vector3) {
@@ -39,13 +45,13 @@ impl Transform3 {
         self.translation.copy_from(rhs);
     }
 
-    pub fn set_translation_from_floats(&mut self, x: f32, y: f32, z: f32) {
+    pub fn set_translation_from_floats(&mut self, x: Number, y: Number, z: Number) {
         self.dirty = true;
         self.translation.x = x;self.translation.y = y;self.translation.z = z;
     }
 
     pub fn scaling(&self) -> Vector3 {
-        self.scaling.clone()
+        self.scaling
     }
 
     pub fn scaling_mut(&mut self, rhs: &Vector3) {
@@ -53,24 +59,24 @@ impl Transform3 {
         self.scaling.copy_from(rhs);
     }
 
-    pub fn set_scaling_from_floats(&mut self, x: f32, y: f32, z: f32) {
+    pub fn set_scaling_from_floats(&mut self, x: Number, y: Number, z: Number) {
         self.dirty = true;
         self.scaling.x = x;self.scaling.y = y;self.scaling.z = z;
     }
 
-    pub fn set_scaling_uniform(&mut self, s: f32) {
+    pub fn set_scaling_uniform(&mut self, s: Number) {
         self.dirty = true;
         self.scaling.x = s;self.scaling.y = s;self.scaling.z = s;
     }
 
-    pub fn get_rotation_euler_angles(&self, coord: &CoordinateSytem3) -> Vector3 {
+    pub fn get_rotation_euler_angles(&self, order: EEulerOrder, coord: &CoordinateSytem3) -> Vector3 {
         let mut result = Vector3::zeros();
-        coord.rotation_matrix_to_euler_angles(&self.rotation, &mut result);
+        coord.rotation_matrix_to_euler_angles(&self.rotation, order, &mut result);
         result
     }
 
     pub fn rotation_matrix(&self) -> Rotation3 {
-        self.rotation.clone()
+        self.rotation
     }
     pub fn rotation_matrix_mut(&mut self, rhs: &Rotation3) {
         self.dirty = true;
@@ -83,9 +89,115 @@ impl Transform3 {
         self.dirty = true;
         self.rotation.clone_from(&rhs.to_rotation_matrix());
     }
-    pub fn set_rotation_from_euler_angles(&mut self, x: Number, y: Number, z: Number, _coord: &CoordinateSytem3) {
+    pub fn set_rotation_from_euler_angles(&mut self, x: Number, y: Number, z: Number, order: EEulerOrder, _coord: &CoordinateSytem3) {
         self.dirty = true;
-        CoordinateSytem3::rotation_matrix_mut_euler_angles(x, y, z, &mut self.rotation);
+        CoordinateSytem3::rotation_matrix_mut_euler_angles(x, y, z, order, &mut self.rotation);
+    }
+    /// 将位移 旋转 缩放从 from 坐标系约定转换到 to 坐标系约定
+    /// * 缩放按轴重排, 手性翻转由旋转与位移承担
+    pub fn convert_coordinate(&mut self, from: &CoordinateSytem3, to: &CoordinateSytem3) {
+        self.dirty = true;
+        let conversion = from.conversion_to(to);
+        self.translation = conversion * self.translation;
+        self.scaling = conversion.abs() * self.scaling;
+        self.pivot = self.pivot.map(|pivot| conversion * pivot);
+        self.scaling_pivot = self.scaling_pivot.map(|pivot| conversion * pivot);
+        let rotation = self.rotation;
+        from.convert_rotation(to, &rotation, &mut self.rotation);
+    }
+    /// 由仿射矩阵分解设置位移 旋转 缩放, 保留旋转缩放中心, 矩阵含切变时切变丢失
+    pub fn set_from_matrix(&mut self, matrix: &Matrix) -> bool {
+        self.dirty = true;
+        let result = CoordinateSytem3::matrix4_decompose_rotation(matrix, Some(&mut self.scaling), Some(&mut self.rotation), Some(&mut self.translation));
+        self.translation -= self.pivot_offset();
+        result
+    }
+
+    pub fn pivot(&self) -> Option<Vector3> {
+        self.pivot
+    }
+    /// 设置旋转中心 - 同 BabylonJS setPivotPoint, 旋转缩放绕该点进行, 位移不受影响
+    pub fn set_pivot(&mut self, pivot: Option<Vector3>) {
+        self.dirty = true;
+        self.pivot = pivot;
+    }
+    pub fn scaling_pivot(&self) -> Option<Vector3> {
+        self.scaling_pivot
+    }
+    /// 设置独立的缩放中心
+    pub fn set_scaling_pivot(&mut self, pivot: Option<Vector3>) {
+        self.dirty = true;
+        self.scaling_pivot = pivot;
+    }
+
+    /// 绕父空间中的点旋转 - rotation 作用于当前旋转之前
+    pub fn rotate_around(&mut self, point: &Vector3, rotation: &Rotation3) {
+        self.dirty = true;
+        let pivot = self.pivot.unwrap_or_else(Vector3::zeros);
+        self.translation = point + rotation * (self.translation + pivot - point) - pivot;
+        self.rotation = rotation * self.rotation;
+    }
+
+    /// 以父空间中的点为中心均匀缩放
+    pub fn scale_around(&mut self, point: &Vector3, factor: Number) {
+        self.dirty = true;
+        let pivot = self.pivot.unwrap_or_else(Vector3::zeros);
+        let scaling_pivot = self.scaling_pivot.unwrap_or(pivot);
+        self.translation = point + (self.translation + pivot - point) * factor
+            + self.rotation * (scaling_pivot - pivot) * (factor - 1.)
+            - pivot;
+        self.scaling *= factor;
+    }
+
+    /// 插值 - 位移 缩放线性插值, 旋转球面插值, 旋转缩放中心取 from
+    pub fn lerp(from: &Transform3, to: &Transform3, amount: Number, result: &mut Transform3) {
+        result.dirty = true;
+        result.translation = from.translation.lerp(&to.translation, amount);
+        result.scaling = from.scaling.lerp(&to.scaling, amount);
+        let mut rotation = Quaternion::identity();
+        CoordinateSytem3::quaternion_slerp(&from.rotation_quaternion(), &to.rotation_quaternion(), amount, &mut rotation);
+        result.rotation = rotation.to_rotation_matrix();
+        result.pivot = from.pivot;
+        result.scaling_pivot = from.scaling_pivot;
+    }
+
+    /// 多个变换按权重混合, 权重自动归一化, 旋转缩放中心取第一个
+    /// * 旋转为对齐半球后的四元数加权平均, 各旋转相差不大时接近球面混合
+    /// * 列表为空或权重和不为正时返回 false
+    pub fn blend(transforms: &[(&Transform3, Number)], result: &mut Transform3) -> bool {
+        let total: Number = transforms.iter().map(|(_, weight)| *weight).sum();
+        if transforms.is_empty() || total <= 0. {
+            return false;
+        }
+
+        let reference = transforms[0].0.rotation_quaternion();
+        let mut translation = Vector3::zeros();
+        let mut scaling = Vector3::zeros();
+        let mut rotation = crate::Vector4::zeros();
+        for (transform, weight) in transforms {
+            let weight = weight / total;
+            translation += transform.translation * weight;
+            scaling += transform.scaling * weight;
+            let quaternion = transform.rotation_quaternion();
+            let sign = if quaternion.coords.dot(&reference.coords) < 0. { -1. } else { 1. };
+            rotation += quaternion.coords * (weight * sign);
+        }
+
+        result.dirty = true;
+        result.translation = translation;
+        result.scaling = scaling;
+        result.rotation = Quaternion::new_normalize(nalgebra::Quaternion::from(rotation)).to_rotation_matrix();
+        result.pivot = transforms[0].0.pivot;
+        result.scaling_pivot = transforms[0].0.scaling_pivot;
+        true
+    }
+
+    /// 旋转缩放中心引起的附加位移
+    /// * 矩阵 = T(translation) * T(pivot) * R * T(-pivot) * T(scaling_pivot) * S * T(-scaling_pivot)
+    fn pivot_offset(&self) -> Vector3 {
+        let pivot = self.pivot.unwrap_or_else(Vector3::zeros);
+        let scaling_pivot = self.scaling_pivot.unwrap_or(pivot);
+        pivot + self.rotation * (scaling_pivot - pivot - scaling_pivot.component_mul(&self.scaling))
     }
     pub fn matrix(&self) -> &Matrix {
         &self.local_matrix
@@ -100,19 +212,26 @@ impl Transform3 {
             
             affine.append_nonuniform_scaling_mut(&self.scaling);
             self.rotation.to_homogeneous().mul_to(&affine, &mut self.local_matrix);
-            self.local_matrix.append_translation_mut(&self.translation);
+            self.local_matrix.append_translation_mut(&(self.translation + self.pivot_offset()));
         }
 
         self.dirty = false;
     }
 }
 
+/// 测试用 - 只有位移的变换
+#[cfg(test)]
+pub(crate) fn translated(x: Number, y: Number, z: Number) -> Transform3 {
+    let mut transform = Transform3::default();
+    transform.set_translation_from_floats(x, y, z);
+    transform
+}
+
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
-    use nalgebra::{Transform3 as NTransform3, Matrix3};
 
-    use crate::{coordiante_system::CoordinateSytem3, Rotation3, Vector3, vector::TToolMatrix};
+    use crate::{coordiante_system::{CoordinateSytem3, ECoordinateSytem3, EEulerOrder, EUpAxis}, Matrix, Rotation3, Vector3, vector::{TToolMatrix, TToolVector3}};
 
     use super::Transform3;
 
@@ -124,7 +243,7 @@ mod test {
         println!("{:?}", transform);
 
         transform.set_scaling_from_floats(1., 2., 3.);
-        transform.set_rotation_from_euler_angles(2., 1.0, -1.0, &coord);
+        transform.set_rotation_from_euler_angles(2., 1.0, -1.0, EEulerOrder::XYZ, &coord);
         transform.set_translation_from_floats(10.0, 0.0, 0.0);
         transform.calc_matrix();
 
@@ -153,6 +272,130 @@ mod test {
         // let rot = Rotation3::from_matrix_unchecked( );
         // println!("{:?}", rot);
     }
+    #[test]
+    fn test_transform_convert_coordinate() {
+        let from = CoordinateSytem3::new(ECoordinateSytem3::Right, EUpAxis::Z);
+        let to = CoordinateSytem3::left();
+        let mut transform = Transform3::default();
+        transform.set_scaling_from_floats(1., 2., 3.);
+        transform.set_rotation_from_euler_angles(0.3, -0.7, 1.1, EEulerOrder::XYZ, &from);
+        transform.set_translation_from_floats(4., 5., 6.);
+        transform.calc_matrix();
+        let source = *transform.matrix();
+
+        transform.convert_coordinate(&from, &to);
+        transform.calc_matrix();
+        assert_relative_eq!(transform.translation(), Vector3::new(4., 6., 5.), epsilon = 0.00001);
+        assert_relative_eq!(transform.scaling(), Vector3::new(1., 3., 2.), epsilon = 0.00001);
+
+        let mut expect = Matrix::identity();
+        from.convert_matrix(&to, &source, &mut expect);
+        assert_relative_eq!(*transform.matrix(), expect, epsilon = 0.00001);
+    }
+
+    /// T(point) * M * T(-point) 形式的参照矩阵
+    fn around(point: &Vector3, m: &Matrix) -> Matrix {
+        let mut result = *m;
+        result.prepend_translation_mut(&-point);
+        result.append_translation_mut(point);
+        result
+    }
+
+    #[test]
+    fn test_transform_pivot() {
+        let mut transform = Transform3::default();
+        transform.set_translation_from_floats(0., 0., 5.);
+        transform.rotation_matrix_mut(&Rotation3::from_axis_angle(&Vector3::z_axis(), crate::consts::FRAC_PI_2));
+        transform.set_pivot(Some(Vector3::new(1., 0., 0.)));
+        transform.calc_matrix();
+        // 旋转中心保持不动
+        let mut result = Vector3::zeros();
+        CoordinateSytem3::transform_coordinates(&Vector3::new(1., 0., 0.), transform.matrix(), &mut result);
+        assert_relative_eq!(result, Vector3::new(1., 0., 5.), epsilon = 0.00001);
+        CoordinateSytem3::transform_coordinates(&Vector3::new(2., 0., 0.), transform.matrix(), &mut result);
+        assert_relative_eq!(result, Vector3::new(1., 1., 5.), epsilon = 0.00001);
+
+        // 独立缩放中心
+        transform.set_scaling_from_floats(2., 3., 1.);
+        transform.set_scaling_pivot(Some(Vector3::new(0., 1., 0.)));
+        transform.calc_matrix();
+        let mut expect = Matrix::new_translation(&Vector3::new(0., 0., 5.));
+        expect *= around(&Vector3::new(1., 0., 0.), &transform.rotation_matrix().to_homogeneous());
+        expect *= around(&Vector3::new(0., 1., 0.), &Matrix::new_nonuniform_scaling(&Vector3::new(2., 3., 1.)));
+        assert_relative_eq!(*transform.matrix(), expect, epsilon = 0.00001);
+
+        // 分解后保留中心
+        let source = *transform.matrix();
+        transform.set_from_matrix(&source);
+        transform.calc_matrix();
+        assert_relative_eq!(*transform.matrix(), source, epsilon = 0.00001);
+        assert_relative_eq!(transform.translation(), Vector3::new(0., 0., 5.), epsilon = 0.00001);
+    }
+
+    #[test]
+    fn test_transform_around_point() {
+        let mut transform = Transform3::default();
+        transform.set_translation_from_floats(3., 1., 0.);
+        transform.set_scaling_from_floats(1., 2., 1.);
+        transform.set_pivot(Some(Vector3::new(0.5, 0., 0.)));
+        transform.set_scaling_pivot(Some(Vector3::new(0., 0.5, 0.)));
+        transform.calc_matrix();
+        let source = *transform.matrix();
+
+        let point = Vector3::new(1., 2., 3.);
+        let rotation = Rotation3::from_axis_angle(&Vector3::y_axis(), 0.3);
+        transform.rotate_around(&point, &rotation);
+        transform.calc_matrix();
+        assert_relative_eq!(*transform.matrix(), around(&point, &rotation.to_homogeneous()) * source, epsilon = 0.0001);
+
+        transform.scale_around(&point, 2.5);
+        transform.calc_matrix();
+        let expect = around(&point, &Matrix::new_scaling(2.5)) * around(&point, &rotation.to_homogeneous()) * source;
+        assert_relative_eq!(*transform.matrix(), expect, epsilon = 0.0001);
+
+        // 多次旋转回到原处不漂移
+        transform.scale_around(&point, 0.4);
+        let step = Rotation3::from_axis_angle(&Vector3::y_axis(), crate::consts::FRAC_PI_2 / 25.);
+        transform.rotate_around(&point, &rotation.inverse());
+        for _ in 0..100 {
+            transform.rotate_around(&point, &step);
+        }
+        transform.calc_matrix();
+        assert_relative_eq!(*transform.matrix(), source, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_transform_blend() {
+        let mut a = Transform3::default();
+        a.set_translation_from_floats(0., 0., 0.);
+        a.set_scaling_uniform(1.);
+        let mut b = Transform3::default();
+        b.set_translation_from_floats(4., 0., 0.);
+        b.set_scaling_uniform(3.);
+        b.rotation_matrix_mut(&Rotation3::from_axis_angle(&Vector3::y_axis(), 1.));
+
+        let mut result = Transform3::default();
+        Transform3::lerp(&a, &b, 0.25, &mut result);
+        assert_relative_eq!(result.translation(), Vector3::new(1., 0., 0.), epsilon = 0.00001);
+        assert_relative_eq!(result.scaling(), Vector3::new(1.5, 1.5, 1.5), epsilon = 0.00001);
+        assert_relative_eq!(result.rotation_matrix(), Rotation3::from_axis_angle(&Vector3::y_axis(), 0.25), epsilon = 0.00001);
+
+        // 两个变换等权混合与中点插值一致
+        let mut blended = Transform3::default();
+        assert!(Transform3::blend(&[(&a, 2.), (&b, 2.)], &mut blended));
+        Transform3::lerp(&a, &b, 0.5, &mut result);
+        assert_relative_eq!(blended.translation(), result.translation(), epsilon = 0.00001);
+        assert_relative_eq!(blended.scaling(), result.scaling(), epsilon = 0.00001);
+        assert_relative_eq!(blended.rotation_matrix(), result.rotation_matrix(), epsilon = 0.00001);
+
+        let mut c = Transform3::default();
+        c.set_translation_from_floats(0., 8., 0.);
+        assert!(Transform3::blend(&[(&a, 1.), (&b, 1.), (&c, 2.)], &mut blended));
+        assert_relative_eq!(blended.translation(), Vector3::new(1., 4., 0.), epsilon = 0.00001);
+        assert!(!Transform3::blend(&[], &mut blended));
+        assert!(!Transform3::blend(&[(&a, 0.)], &mut blended));
+    }
+
     #[test]
     fn test_transform_right() {
         let coord = CoordinateSytem3::right();
@@ -161,7 +404,7 @@ mod test {
         println!("{:?}", transform);
 
         transform.set_scaling_from_floats(1., 2., 3.);
-        transform.set_rotation_from_euler_angles(2., 1.0, -1.0, &coord);
+        transform.set_rotation_from_euler_angles(2., 1.0, -1.0, EEulerOrder::XYZ, &coord);
         transform.set_translation_from_floats(10.0, 0.0, 0.0);
         transform.calc_matrix();
 
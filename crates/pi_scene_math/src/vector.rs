@@ -1,6 +1,6 @@
 
 
-use crate::{Number, Vector3, Matrix, Quaternion, Rotation3, Isometry3};
+use crate::{Number, Vector3, Vector4, Matrix, Quaternion, Rotation3, Isometry3, coordiante_system::EEulerOrder};
 
 pub trait TMinimizeMaximize {
     /// 取得两个数据结构中 每个分量的最小值的集合
@@ -41,8 +41,8 @@ impl TMinimizeMaximize for Vector3 {
 }
 
 pub trait TToolVector3 {
-    fn up() -> Vector3;
-    fn down() -> Vector3;
+    fn up(&self) -> Vector3;
+    fn down(&self) -> Vector3;
     fn backward(&self) -> Vector3;
     fn forward(&self) -> Vector3;
     fn right() -> Vector3;
@@ -58,7 +58,8 @@ pub trait TToolVector3 {
     fn transform_coordinates_floats(x: Number, y: Number, z: Number, transformation: &Matrix, result: &mut Vector3);
     fn transform_normal(v0: &Vector3, transformation: &Matrix, result: &mut Vector3);
     fn transform_normal_floats(x: Number, y: Number, z: Number, transformation: &Matrix, result: &mut Vector3);
-    fn rotation_from_axis(axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, result: &mut Vector3);
+    /// 三个基向量 (局部 X Y Z 轴旋转后的方向) 对应的欧拉角
+    fn rotation_from_axis(axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, order: EEulerOrder, result: &mut Vector3);
     fn rotate_by_quaternion(v0: &Vector3, quaternion: &Quaternion, result: &mut Vector3);
     fn rotate_by_quaternion_around_point(v0: &Vector3, quaternion: &Quaternion, point: Vector3, result: &mut Vector3);
     // fn dot(&self) -> Number;
@@ -68,33 +69,41 @@ pub trait TToolVector3 {
 
 
 pub trait TToolRotation {
-    /// * `x` Pitch
-    /// * `y` Yaw
-    /// * `z` Roll
-    fn quaternion_from_euler_angles(x: Number, y: Number, z: Number) -> Quaternion;
-    fn quaternion_mut_euler_angles(x: Number, y: Number, z: Number, result: &mut Quaternion);
+    /// * `x` `y` `z` 绕 X Y Z 轴的角度 (Pitch Yaw Roll)
+    /// * `order` 旋转顺序
+    fn quaternion_from_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder) -> Quaternion;
+    fn quaternion_mut_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Quaternion);
     fn quaternion_from_unit_vector(axis: &nalgebra::Unit<Vector3>, vec_to: &Vector3) -> Quaternion;
     fn quaternion_from_rotation<T: nalgebra::RealField>(quaternion: &mut nalgebra::Quaternion<T>, rotmat: &nalgebra::Rotation3<T>);
     fn quaternion_to_rotation(quaternion: &nalgebra::Quaternion<Number>, rotation: &mut Rotation3);
     
-    /// * `x` Pitch
-    /// * `y` Yaw
-    /// * `z` Roll
+    /// 按 `EEulerOrder::ZXY` 顺序: 先 Roll(Z) 再 Pitch(X) 最后 Yaw(Y)
     fn quaternion_mut_yaw_pitch_roll(&self, yaw: Number, pitch: Number, roll: Number, result: &mut Quaternion);
     fn quaternion_mut_axis(&self, axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, result: &mut Quaternion);
-    fn quaternion_to_euler_angles(&self, rotation: &Quaternion, result: &mut Vector3);
-    fn rotation_matrix_from_euler_angles_toref(x: Number, y: Number, z: Number, result: &mut Rotation3);
-    fn rotation_matrix_from_euler_angles(x: Number, y: Number, z: Number) -> Rotation3;
-    fn rotation_matrix_mut_euler_angles(x: Number, y: Number, z: Number, result: &mut Rotation3);
+    fn quaternion_to_euler_angles(&self, rotation: &Quaternion, order: EEulerOrder, result: &mut Vector3);
+    fn rotation_matrix_from_euler_angles_toref(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Rotation3);
+    fn rotation_matrix_from_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder) -> Rotation3;
+    fn rotation_matrix_mut_euler_angles(x: Number, y: Number, z: Number, order: EEulerOrder, result: &mut Rotation3);
     fn rotation_matrix_from_axises(axis1: &Vector3, axis2: &Vector3, axis3: &Vector3) -> Rotation3;
     fn quaternion_from_axis_angle(axis1: &Vector3, radian: Number) -> Quaternion;
     
-    /// * `x` Pitch
-    /// * `y` Yaw
-    /// * `z` Roll
+    /// 按 `EEulerOrder::ZXY` 顺序: 先 Roll(Z) 再 Pitch(X) 最后 Yaw(Y)
     fn rotation_matrix_mut_yaw_pitch_roll(&self, yaw: Number, pitch: Number, roll: Number, result: &mut Rotation3);
     fn rotation_matrix_mut_axis(&self, axis1: &Vector3, axis2: &Vector3, axis3: &Vector3, result: &mut Rotation3);
-    fn rotation_matrix_to_euler_angles(&self, rotation: &Rotation3, result: &mut Vector3);
+    fn rotation_matrix_to_euler_angles(&self, rotation: &Rotation3, order: EEulerOrder, result: &mut Vector3);
+
+    /// 球面线性插值, 走最短路径
+    fn quaternion_slerp(from: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion);
+    /// 分量线性插值后归一化, 走最短路径, 角速度不均匀但开销小
+    fn quaternion_nlerp(from: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion);
+    /// 球面四边形插值 - `control1` `control2` 为 from to 的 `quaternion_squad_control`
+    fn quaternion_squad(from: &Quaternion, control1: &Quaternion, control2: &Quaternion, to: &Quaternion, amount: Number, result: &mut Quaternion);
+    /// squad 控制点 - 由当前及前后相邻关键帧计算, 首尾关键帧以自身作为缺失的相邻帧
+    fn quaternion_squad_control(prev: &Quaternion, current: &Quaternion, next: &Quaternion, result: &mut Quaternion);
+    /// 三次 Hermite 插值后归一化 (glTF CUBICSPLINE)
+    /// * `tangent1` 为 value1 的出切线, `tangent2` 为 value2 的入切线, 均为 (x, y, z, w) 未归一化四元数
+    /// * `frame_delta` 两关键帧间隔, 切线按此缩放
+    fn quaternion_hermite(value1: &Quaternion, tangent1: &Vector4, value2: &Quaternion, tangent2: &Vector4, amount: Number, frame_delta: Number, result: &mut Quaternion);
 }
 
 pub trait TToolMatrix {
@@ -106,7 +115,7 @@ pub trait TToolMatrix {
     fn matrix4_decompose(m: &Matrix, scaling: Option<&mut Vector3>, quaternion: Option<&mut Quaternion>, translation: Option<&mut Vector3>) -> bool;
     fn matrix4_decompose_rotation(m: &Matrix, scaling: Option<&mut Vector3>, rotation: Option<&mut Rotation3>, translation: Option<&mut Vector3>) -> bool;
     fn matrix4_compose(scaling: &Vector3, quaternion: &Quaternion, translation: &Vector3, result: &mut Matrix);
-    fn matrix4_compose_euler_angle(scaling: &Vector3, eulers: &Vector3, translation: &Vector3, result: &mut Matrix);
+    fn matrix4_compose_euler_angle(scaling: &Vector3, eulers: &Vector3, order: EEulerOrder, translation: &Vector3, result: &mut Matrix);
     fn matrix4_compose_rotation(scaling: &Vector3, rotmat: &Rotation3, translation: &Vector3, result: &mut Matrix);
     fn matrix4_compose_no_rotation(scaling: &Vector3, translation: &Vector3, result: &mut Matrix);
     fn matrix4_compose_quaternion(scaling: &Vector3, quaternion: &Quaternion, translation: &Vector3, result: &mut Matrix);